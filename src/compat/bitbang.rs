@@ -0,0 +1,415 @@
+//! src/compat/bitbang.rs
+//! Software (bit-banged) I2C master over two open-drain GPIOs.
+//!
+//! Useful as a fallback when the hardware I2C peripheral is unavailable or
+//! misbehaving: `BitbangI2c` implements `I2cCompat`, so the scanner and the
+//! explorer run on it unchanged.
+
+use crate::compat::delay_compat::DelayCompat;
+use crate::compat::gpio_compat::{InputPinCompat, OutputPinCompat};
+use crate::compat::i2c_compat::I2cCompat;
+use crate::error::BitbangError;
+
+/// Default half clock period, giving roughly 100 kHz SCL.
+const DEFAULT_HALF_PERIOD_US: u32 = 5;
+
+/// Bit-banged I2C master.
+///
+/// Both pins must be configured as open-drain with external pull-ups:
+/// driving a pin high releases the line, driving it low pulls it down.
+/// `SDA` is read back to sample ACK and data bits. Clock stretching is not
+/// supported, so slow targets may need a longer half period.
+pub struct BitbangI2c<SDA, SCL, DELAY> {
+    sda: SDA,
+    scl: SCL,
+    delay: DELAY,
+    half_period_us: u32,
+}
+
+impl<SDA, SCL, DELAY> BitbangI2c<SDA, SCL, DELAY>
+where
+    SDA: OutputPinCompat + InputPinCompat,
+    SCL: OutputPinCompat,
+    DELAY: DelayCompat,
+{
+    /// Create a bus running at roughly 100 kHz.
+    pub fn new(sda: SDA, scl: SCL, delay: DELAY) -> Self {
+        Self {
+            sda,
+            scl,
+            delay,
+            half_period_us: DEFAULT_HALF_PERIOD_US,
+        }
+    }
+
+    /// Set the half clock period in microseconds (SCL ≈ 500 / `us` kHz).
+    pub fn with_half_period_us(mut self, us: u32) -> Self {
+        self.half_period_us = us;
+        self
+    }
+
+    /// Release the pins and the delay provider.
+    pub fn release(self) -> (SDA, SCL, DELAY) {
+        (self.sda, self.scl, self.delay)
+    }
+
+    fn wait(&mut self) {
+        self.delay.delay_us(self.half_period_us);
+    }
+
+    fn sda(&mut self, high: bool) -> Result<(), BitbangError> {
+        let res = if high {
+            OutputPinCompat::set_high(&mut self.sda)
+        } else {
+            OutputPinCompat::set_low(&mut self.sda)
+        };
+        res.map_err(|_| BitbangError::Pin)
+    }
+
+    fn scl(&mut self, high: bool) -> Result<(), BitbangError> {
+        let res = if high {
+            self.scl.set_high()
+        } else {
+            self.scl.set_low()
+        };
+        res.map_err(|_| BitbangError::Pin)
+    }
+
+    fn start(&mut self) -> Result<(), BitbangError> {
+        // Also serves as a repeated start when issued after a data byte.
+        self.sda(true)?;
+        self.wait();
+        self.scl(true)?;
+        self.wait();
+        self.sda(false)?;
+        self.wait();
+        self.scl(false)?;
+        self.wait();
+        Ok(())
+    }
+
+    fn stop(&mut self) -> Result<(), BitbangError> {
+        self.sda(false)?;
+        self.wait();
+        self.scl(true)?;
+        self.wait();
+        self.sda(true)?;
+        self.wait();
+        Ok(())
+    }
+
+    fn write_bit(&mut self, bit: bool) -> Result<(), BitbangError> {
+        self.sda(bit)?;
+        self.wait();
+        self.scl(true)?;
+        self.wait();
+        self.scl(false)?;
+        Ok(())
+    }
+
+    fn read_bit(&mut self) -> Result<bool, BitbangError> {
+        self.sda(true)?;
+        self.wait();
+        self.scl(true)?;
+        self.wait();
+        let bit = self.sda.is_high().map_err(|_| BitbangError::Pin)?;
+        self.scl(false)?;
+        Ok(bit)
+    }
+
+    /// Clock out one byte MSB first. Returns `true` if the target ACKed.
+    fn write_byte(&mut self, byte: u8) -> Result<bool, BitbangError> {
+        for i in (0..8).rev() {
+            self.write_bit(byte & (1 << i) != 0)?;
+        }
+        Ok(!self.read_bit()?)
+    }
+
+    /// Clock in one byte MSB first, then ACK (`ack = true`) or NACK it.
+    fn read_byte(&mut self, ack: bool) -> Result<u8, BitbangError> {
+        let mut byte = 0u8;
+        for _ in 0..8 {
+            byte = (byte << 1) | self.read_bit()? as u8;
+        }
+        self.write_bit(!ack)?;
+        Ok(byte)
+    }
+
+    fn send_addr(&mut self, addr: u8, read: bool) -> Result<(), BitbangError> {
+        self.start()?;
+        if self.write_byte((addr << 1) | read as u8)? {
+            Ok(())
+        } else {
+            Err(BitbangError::Nack)
+        }
+    }
+
+    fn send_bytes(&mut self, bytes: &[u8]) -> Result<(), BitbangError> {
+        for &b in bytes {
+            if !self.write_byte(b)? {
+                return Err(BitbangError::Nack);
+            }
+        }
+        Ok(())
+    }
+
+    fn recv_bytes(&mut self, buffer: &mut [u8]) -> Result<(), BitbangError> {
+        let len = buffer.len();
+        for (i, b) in buffer.iter_mut().enumerate() {
+            *b = self.read_byte(i + 1 < len)?;
+        }
+        Ok(())
+    }
+
+    /// Run `f` and always terminate the transaction with a STOP condition.
+    fn transaction<T>(
+        &mut self,
+        f: impl FnOnce(&mut Self) -> Result<T, BitbangError>,
+    ) -> Result<T, BitbangError> {
+        let res = f(self);
+        let stop = self.stop();
+        let value = res?;
+        stop.map(|_| value)
+    }
+}
+
+impl<SDA, SCL, DELAY> I2cCompat for BitbangI2c<SDA, SCL, DELAY>
+where
+    SDA: OutputPinCompat + InputPinCompat,
+    SCL: OutputPinCompat,
+    DELAY: DelayCompat,
+{
+    type Error = BitbangError;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.transaction(|bus| {
+            bus.send_addr(addr, false)?;
+            bus.send_bytes(bytes)
+        })
+    }
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(|bus| {
+            bus.send_addr(addr, true)?;
+            bus.recv_bytes(buffer)
+        })
+    }
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.transaction(|bus| {
+            bus.send_addr(addr, false)?;
+            bus.send_bytes(bytes)?;
+            bus.send_addr(addr, true)?;
+            bus.recv_bytes(buffer)
+        })
+    }
+
+    fn probe(&mut self, addr: u8) -> Result<bool, Self::Error> {
+        match self.transaction(|bus| bus.send_addr(addr, false)) {
+            Ok(_) => Ok(true),
+            Err(BitbangError::Nack) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn is_nack(&self, error: &Self::Error) -> bool {
+        matches!(error, BitbangError::Nack)
+    }
+}
+
+#[cfg(feature = "ehal_1_0")]
+impl embedded_hal_1::i2c::Error for BitbangError {
+    fn kind(&self) -> embedded_hal_1::i2c::ErrorKind {
+        match self {
+            BitbangError::Nack => embedded_hal_1::i2c::ErrorKind::NoAcknowledge(
+                embedded_hal_1::i2c::NoAcknowledgeSource::Unknown,
+            ),
+            BitbangError::Pin => embedded_hal_1::i2c::ErrorKind::Other,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "ehal_1_0"))]
+mod tests {
+    use super::*;
+
+    mod ehal_1_0_tests {
+        use super::*;
+        use core::cell::{Cell, RefCell};
+        use embedded_hal_1::delay::DelayNs;
+        use embedded_hal_1::digital::{ErrorType, InputPin, OutputPin};
+
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        enum Event {
+            Start,
+            Byte(u8),
+            Stop,
+        }
+
+        /// Open-drain bus with a simulated write-only target: it decodes what
+        /// the master clocks out and pulls SDA low in the ACK slot when
+        /// `acks` is set. `held_low` forces SDA low regardless.
+        struct Bus {
+            sda_released: Cell<bool>,
+            scl: Cell<bool>,
+            target_low: Cell<bool>,
+            held_low: Cell<bool>,
+            acks: Cell<bool>,
+            bits: Cell<u8>,
+            byte: Cell<u8>,
+            events: RefCell<heapless::Vec<Event, 16>>,
+        }
+
+        impl Bus {
+            fn new() -> Self {
+                Self {
+                    sda_released: Cell::new(true),
+                    scl: Cell::new(true),
+                    target_low: Cell::new(false),
+                    held_low: Cell::new(false),
+                    acks: Cell::new(true),
+                    bits: Cell::new(0),
+                    byte: Cell::new(0),
+                    events: RefCell::new(heapless::Vec::new()),
+                }
+            }
+
+            fn sda(&self) -> bool {
+                self.sda_released.get() && !self.target_low.get() && !self.held_low.get()
+            }
+
+            fn push(&self, event: Event) {
+                self.events.borrow_mut().push(event).unwrap();
+            }
+
+            fn drive(&self, sda: bool, high: bool) {
+                let (old_sda, old_scl) = (self.sda(), self.scl.get());
+                if sda {
+                    self.sda_released.set(high);
+                } else {
+                    self.scl.set(high);
+                }
+                let (new_sda, new_scl) = (self.sda(), self.scl.get());
+
+                if old_scl && new_scl && old_sda != new_sda {
+                    // SDA moving while SCL is high: START when falling, STOP when rising.
+                    self.bits.set(0);
+                    self.target_low.set(false);
+                    self.push(if new_sda { Event::Stop } else { Event::Start });
+                } else if !old_scl && new_scl && self.bits.get() < 8 {
+                    self.byte.set((self.byte.get() << 1) | new_sda as u8);
+                    self.bits.set(self.bits.get() + 1);
+                    if self.bits.get() == 8 {
+                        self.push(Event::Byte(self.byte.get()));
+                    }
+                } else if old_scl && !new_scl && self.bits.get() == 8 {
+                    if self.target_low.get() {
+                        // End of the ACK clock: release SDA, next byte.
+                        self.target_low.set(false);
+                        self.bits.set(0);
+                    } else {
+                        self.target_low.set(self.acks.get());
+                        if !self.acks.get() {
+                            self.bits.set(0);
+                        }
+                    }
+                }
+            }
+        }
+
+        struct FakePin<'a> {
+            bus: &'a Bus,
+            sda: bool,
+        }
+
+        impl ErrorType for FakePin<'_> {
+            type Error = core::convert::Infallible;
+        }
+
+        impl OutputPin for FakePin<'_> {
+            fn set_low(&mut self) -> Result<(), Self::Error> {
+                self.bus.drive(self.sda, false);
+                Ok(())
+            }
+            fn set_high(&mut self) -> Result<(), Self::Error> {
+                self.bus.drive(self.sda, true);
+                Ok(())
+            }
+        }
+
+        impl InputPin for FakePin<'_> {
+            fn is_high(&mut self) -> Result<bool, Self::Error> {
+                Ok(if self.sda {
+                    self.bus.sda()
+                } else {
+                    self.bus.scl.get()
+                })
+            }
+            fn is_low(&mut self) -> Result<bool, Self::Error> {
+                InputPin::is_high(self).map(|high| !high)
+            }
+        }
+
+        struct NoDelay;
+
+        impl DelayNs for NoDelay {
+            fn delay_ns(&mut self, _ns: u32) {}
+        }
+
+        #[test]
+        fn test_bitbang_ack_and_nack() {
+            let bus = Bus::new();
+            let mut i2c = BitbangI2c::new(
+                FakePin {
+                    bus: &bus,
+                    sda: true,
+                },
+                FakePin {
+                    bus: &bus,
+                    sda: false,
+                },
+                NoDelay,
+            );
+
+            // The target sees START, 0x3C << 1 | W, both bytes MSB first, STOP.
+            assert!(I2cCompat::write(&mut i2c, 0x3C, &[0x00, 0xAE]).is_ok());
+            assert_eq!(
+                bus.events.borrow().as_slice(),
+                &[
+                    Event::Start,
+                    Event::Byte(0x78),
+                    Event::Byte(0x00),
+                    Event::Byte(0xAE),
+                    Event::Stop,
+                ]
+            );
+            assert_eq!(i2c.probe(0x3C), Ok(true));
+
+            // A line stuck low ACKs every byte and reads back as zeros.
+            bus.held_low.set(true);
+            let mut buf = [0xFFu8; 2];
+            assert!(I2cCompat::read(&mut i2c, 0x3C, &mut buf).is_ok());
+            assert_eq!(buf, [0x00; 2]);
+            bus.held_low.set(false);
+
+            // The target stops answering: the address byte is NACKed and
+            // the transaction still ends with STOP.
+            bus.acks.set(false);
+            bus.events.borrow_mut().clear();
+            assert_eq!(i2c.probe(0x3C), Ok(false));
+            let err = I2cCompat::write(&mut i2c, 0x3C, &[0x00]).unwrap_err();
+            assert!(i2c.is_nack(&err));
+            assert_eq!(
+                bus.events.borrow().as_slice(),
+                &[
+                    Event::Start,
+                    Event::Byte(0x78),
+                    Event::Stop,
+                    Event::Start,
+                    Event::Byte(0x78),
+                    Event::Stop,
+                ]
+            );
+        }
+    }
+}
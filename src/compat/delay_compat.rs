@@ -0,0 +1,69 @@
+//! src/compat/delay_compat.rs
+//! Blocking delay abstraction over embedded-hal 0.2 and 1.0.
+
+/// common blocking delay trait
+pub trait DelayCompat {
+    /// Pause execution for at least `us` microseconds.
+    fn delay_us(&mut self, us: u32);
+}
+
+// ========== ehal 1.0 ==========
+#[cfg(feature = "ehal_1_0")]
+impl<D> DelayCompat for D
+where
+    D: embedded_hal_1::delay::DelayNs,
+{
+    fn delay_us(&mut self, us: u32) {
+        embedded_hal_1::delay::DelayNs::delay_us(self, us)
+    }
+}
+
+// ========== ehal 0.2.x ==========
+#[cfg(all(feature = "ehal_0_2", not(feature = "ehal_1_0")))]
+impl<D> DelayCompat for D
+where
+    D: embedded_hal_0_2::blocking::delay::DelayUs<u32>,
+{
+    fn delay_us(&mut self, us: u32) {
+        embedded_hal_0_2::blocking::delay::DelayUs::delay_us(self, us)
+    }
+}
+
+/// A busy-wait without a timer, counting spin-loop iterations.
+///
+/// It has no notion of time, so it deliberately does not implement
+/// [`DelayCompat`]: the real duration of [`SpinDelay::spin`] depends on the
+/// CPU clock. It is the default delay of
+/// [`PrefixExecutor`](crate::explore::explorer::PrefixExecutor); plug in a HAL
+/// delay with `with_delay` for pauses in microseconds.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SpinDelay;
+
+impl SpinDelay {
+    /// Spins `iterations` times.
+    pub fn spin(&mut self, iterations: u32) {
+        for _ in 0..iterations {
+            core::hint::spin_loop();
+        }
+    }
+}
+
+/// The pause a [`PrefixExecutor`](crate::explore::explorer::PrefixExecutor)
+/// waits with: microseconds for every [`DelayCompat`], spin-loop iterations
+/// for [`SpinDelay`].
+pub trait ExecutorDelay {
+    /// Pause for `amount` units of this delay.
+    fn pause(&mut self, amount: u32);
+}
+
+impl<D: DelayCompat> ExecutorDelay for D {
+    fn pause(&mut self, us: u32) {
+        self.delay_us(us);
+    }
+}
+
+impl ExecutorDelay for SpinDelay {
+    fn pause(&mut self, iterations: u32) {
+        self.spin(iterations);
+    }
+}
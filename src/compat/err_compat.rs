@@ -12,6 +12,15 @@ use embedded_hal_1::i2c as i2c_1_0;
 pub trait HalErrorExt {
     /// Convert HAL error into unified `ErrorKind`, optionally with device address
     fn to_compat(&self, addr: Option<u8>) -> ErrorKind;
+
+    /// Convert HAL error into unified `ErrorKind`, keeping the device address
+    /// alongside it for diagnostics.
+    fn to_compat_at(&self, addr: u8) -> AddressedError {
+        AddressedError {
+            kind: self.to_compat(Some(addr)),
+            addr,
+        }
+    }
 }
 
 #[cfg(all(feature = "ehal_0_2", not(feature = "ehal_1_0")))]
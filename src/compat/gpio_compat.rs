@@ -0,0 +1,87 @@
+//! src/compat/gpio_compat.rs
+//! Digital pin abstraction over embedded-hal 0.2 and 1.0.
+use core::fmt::Debug;
+
+/// common output pin trait
+pub trait OutputPinCompat {
+    type Error: Debug;
+
+    fn set_high(&mut self) -> Result<(), Self::Error>;
+    fn set_low(&mut self) -> Result<(), Self::Error>;
+}
+
+/// common input pin trait
+pub trait InputPinCompat {
+    type Error: Debug;
+
+    fn is_high(&mut self) -> Result<bool, Self::Error>;
+    fn is_low(&mut self) -> Result<bool, Self::Error>;
+}
+
+// ========== ehal 1.0 ==========
+#[cfg(feature = "ehal_1_0")]
+impl<P> OutputPinCompat for P
+where
+    P: embedded_hal_1::digital::OutputPin,
+{
+    type Error = P::Error;
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        embedded_hal_1::digital::OutputPin::set_high(self)
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        embedded_hal_1::digital::OutputPin::set_low(self)
+    }
+}
+
+#[cfg(feature = "ehal_1_0")]
+impl<P> InputPinCompat for P
+where
+    P: embedded_hal_1::digital::InputPin,
+{
+    type Error = P::Error;
+
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        embedded_hal_1::digital::InputPin::is_high(self)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        embedded_hal_1::digital::InputPin::is_low(self)
+    }
+}
+
+// ========== ehal 0.2.x ==========
+#[cfg(all(feature = "ehal_0_2", not(feature = "ehal_1_0")))]
+impl<P, E> OutputPinCompat for P
+where
+    P: embedded_hal_0_2::digital::v2::OutputPin<Error = E>,
+    E: Debug,
+{
+    type Error = E;
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        embedded_hal_0_2::digital::v2::OutputPin::set_high(self)
+    }
+
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        embedded_hal_0_2::digital::v2::OutputPin::set_low(self)
+    }
+}
+
+#[cfg(all(feature = "ehal_0_2", not(feature = "ehal_1_0")))]
+impl<P, E> InputPinCompat for P
+where
+    P: embedded_hal_0_2::digital::v2::InputPin<Error = E>,
+    E: Debug,
+{
+    type Error = E;
+
+    fn is_high(&mut self) -> Result<bool, Self::Error> {
+        embedded_hal_0_2::digital::v2::InputPin::is_high(self)
+    }
+
+    fn is_low(&mut self) -> Result<bool, Self::Error> {
+        embedded_hal_0_2::digital::v2::InputPin::is_low(self)
+    }
+}
@@ -0,0 +1,116 @@
+//! src/compat/guard.rs
+//! I2C wrapper that keeps diagnostics away from protected addresses.
+
+use crate::compat::i2c_compat::I2cCompat;
+use crate::compat::util::BitFlags;
+use core::fmt::Debug;
+
+/// Error of a [`GuardedI2c`] bus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuardError<E> {
+    /// The address is excluded; nothing was sent on the bus.
+    Excluded(u8),
+    /// The underlying bus failed.
+    Bus(E),
+}
+
+#[cfg(feature = "ehal_1_0")]
+impl<E> embedded_hal_1::i2c::Error for GuardError<E>
+where
+    E: embedded_hal_1::i2c::Error,
+{
+    fn kind(&self) -> embedded_hal_1::i2c::ErrorKind {
+        match self {
+            GuardError::Excluded(_) => embedded_hal_1::i2c::ErrorKind::NoAcknowledge(
+                embedded_hal_1::i2c::NoAcknowledgeSource::Address,
+            ),
+            GuardError::Bus(e) => e.kind(),
+        }
+    }
+}
+
+/// An `I2cCompat` wrapper that never touches the excluded addresses.
+///
+/// Probes of an excluded address report "absent" and any other access fails
+/// with [`GuardError::Excluded`], both without a single bus transaction. Since
+/// every scanner and explorer only talks to the bus through `I2cCompat`,
+/// wrapping the bus once protects critical devices (a PMIC, a fuel gauge...)
+/// from all of them.
+///
+/// ```ignore
+/// let mut guarded = GuardedI2c::new(&mut i2c, &[0x34]); // keep off the PMIC
+/// scan_i2c(&mut guarded, &mut serial, 0x00)?;
+/// pruning_sort!(explorer, &mut guarded, &mut serial, PREFIX, N, BUF, MAX_DEPS)?;
+/// ```
+pub struct GuardedI2c<'a, I2C> {
+    inner: &'a mut I2C,
+    excluded: BitFlags,
+}
+
+impl<'a, I2C> GuardedI2c<'a, I2C> {
+    /// Wrap `inner`, excluding `skip_addrs` (7-bit addresses; others ignored).
+    pub fn new(inner: &'a mut I2C, skip_addrs: &[u8]) -> Self {
+        let mut excluded = BitFlags::new();
+        for &addr in skip_addrs {
+            excluded.set(addr as usize).ok();
+        }
+        Self::with_mask(inner, excluded)
+    }
+
+    /// Wrap `inner`, excluding every address whose bit is set in `excluded`.
+    pub fn with_mask(inner: &'a mut I2C, excluded: BitFlags) -> Self {
+        Self { inner, excluded }
+    }
+
+    /// The excluded addresses.
+    pub fn excluded(&self) -> BitFlags {
+        self.excluded
+    }
+
+    /// Fails with [`GuardError::Excluded`] if `addr` must not be touched.
+    fn check<E>(&self, addr: u8) -> Result<(), GuardError<E>> {
+        if self.excluded.get(addr as usize).unwrap_or(false) {
+            return Err(GuardError::Excluded(addr));
+        }
+        Ok(())
+    }
+}
+
+impl<I2C> I2cCompat for GuardedI2c<'_, I2C>
+where
+    I2C: I2cCompat,
+    I2C::Error: Debug,
+{
+    type Error = GuardError<I2C::Error>;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.check(addr)?;
+        self.inner.write(addr, bytes).map_err(GuardError::Bus)
+    }
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.check(addr)?;
+        self.inner.read(addr, buffer).map_err(GuardError::Bus)
+    }
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.check(addr)?;
+        self.inner
+            .write_read(addr, bytes, buffer)
+            .map_err(GuardError::Bus)
+    }
+
+    fn probe(&mut self, addr: u8) -> Result<bool, Self::Error> {
+        if self.check::<I2C::Error>(addr).is_err() {
+            return Ok(false);
+        }
+        self.inner.probe(addr).map_err(GuardError::Bus)
+    }
+
+    fn is_nack(&self, error: &Self::Error) -> bool {
+        match error {
+            GuardError::Excluded(_) => true,
+            GuardError::Bus(e) => self.inner.is_nack(e),
+        }
+    }
+}
@@ -3,6 +3,9 @@ use core::fmt::Debug;
 #[cfg(feature = "ehal_1_0")]
 use embedded_hal_1::i2c::{Error as Ehal1Error, ErrorKind as Ehal1ErrorKind}; // Add this for ehal 1.0 ErrorKind
 
+/// Largest dummy read [`I2cCompat::probe_read`] performs.
+pub const PROBE_READ_MAX: usize = 32;
+
 /// common I2C trait
 pub trait I2cCompat {
     type Error: Debug;
@@ -14,6 +17,60 @@ pub trait I2cCompat {
     /// This is typically implemented by a 1-byte write or a dummy read,
     /// which checks for an ACK from the device.
     fn probe(&mut self, addr: u8) -> Result<bool, Self::Error>;
+    /// Check if a device exists at the given I2C address, letting `classify`
+    /// decide which errors mean "no device here".
+    ///
+    /// Performs a zero-length write. `Ok(())` means present; an error for
+    /// which `classify` returns `true` means absent (`Ok(false)`); any other
+    /// error is propagated. This is the escape hatch for ehal 0.2, where
+    /// `probe` and `is_nack` cannot tell a NACK from a bus fault.
+    fn probe_with<F>(&mut self, addr: u8, classify: F) -> Result<bool, Self::Error>
+    where
+        F: Fn(&Self::Error) -> bool,
+    {
+        match self.write(addr, &[]) {
+            Ok(_) => Ok(true),
+            Err(e) if classify(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+    /// Check if a device exists by reading `len` dummy bytes from it.
+    ///
+    /// For devices that ACK reads but not writes (write-protected EEPROMs,
+    /// some sensors). A NACK means absent (`Ok(false)`); any other error is
+    /// propagated. `len` is clamped to `1..=PROBE_READ_MAX`, since many
+    /// HALs reject zero-length reads.
+    fn probe_read(&mut self, addr: u8, len: usize) -> Result<bool, Self::Error> {
+        let mut buf = [0u8; PROBE_READ_MAX];
+        match self.read(addr, &mut buf[..len.clamp(1, PROBE_READ_MAX)]) {
+            Ok(_) => Ok(true),
+            Err(e) if self.is_nack(&e) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+    /// Check if the error is a NACK error.
+    fn is_nack(&self, error: &Self::Error) -> bool;
+}
+
+/// Async counterpart of [`I2cCompat`], for executors such as embassy.
+///
+/// Errors are the HAL's own, so they convert through `HalErrorExt` exactly
+/// like the blocking ones.
+#[cfg(feature = "ehal_async")]
+#[allow(async_fn_in_trait)]
+pub trait I2cCompatAsync {
+    type Error: Debug;
+
+    async fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error>;
+    async fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error>;
+    async fn write_read(
+        &mut self,
+        addr: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error>;
+    /// Check if a device exists at the given I2C address (see [`I2cCompat::probe`]).
+    async fn probe(&mut self, addr: u8) -> Result<bool, Self::Error>;
     /// Check if the error is a NACK error.
     fn is_nack(&self, error: &Self::Error) -> bool;
 }
@@ -103,6 +160,51 @@ where
     }
 }
 
+// ========== embedded-hal-async ==========
+#[cfg(feature = "ehal_async")]
+impl<I2C> I2cCompatAsync for I2C
+where
+    I2C: embedded_hal_async::i2c::I2c,
+    I2C::Error: embedded_hal_1::i2c::Error,
+{
+    type Error = I2C::Error;
+
+    async fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        embedded_hal_async::i2c::I2c::write(self, addr, bytes).await
+    }
+
+    async fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        embedded_hal_async::i2c::I2c::read(self, addr, buffer).await
+    }
+
+    async fn write_read(
+        &mut self,
+        addr: u8,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        embedded_hal_async::i2c::I2c::write_read(self, addr, bytes, buffer).await
+    }
+
+    async fn probe(&mut self, addr: u8) -> Result<bool, Self::Error> {
+        match embedded_hal_async::i2c::I2c::transaction(
+            self,
+            addr,
+            &mut [embedded_hal_async::i2c::Operation::Write(&[])],
+        )
+        .await
+        {
+            Ok(_) => Ok(true),
+            Err(e) if matches!(e.kind(), Ehal1ErrorKind::NoAcknowledge(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn is_nack(&self, error: &Self::Error) -> bool {
+        matches!(error.kind(), Ehal1ErrorKind::NoAcknowledge(_))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,11 +314,11 @@ mod tests {
             let mut i2c = DummyI2c;
             let mut buf = [0u8; 4];
 
-            assert!(i2c.write(0x42, &[1, 2, 3]).is_ok());
-            assert!(i2c.read(0x42, &mut buf).is_ok());
+            assert!(Write::write(&mut i2c, 0x42, &[1, 2, 3]).is_ok());
+            assert!(Read::read(&mut i2c, 0x42, &mut buf).is_ok());
             assert_eq!(buf, [0xAA; 4]);
 
-            assert!(i2c.write_read(0x42, &[9], &mut buf).is_ok());
+            assert!(WriteRead::write_read(&mut i2c, 0x42, &[9], &mut buf).is_ok());
             assert_eq!(buf, [0x55; 4]);
         }
     }
@@ -0,0 +1,233 @@
+//! src/compat/mock.rs
+//! Scripted I2C buses for host tests of the scanner and the explorer.
+
+use crate::compat::i2c_compat::I2cCompat;
+use crate::explore::trace::TraceRecord;
+use core::fmt;
+
+/// Error reported by [`OracleI2c`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OracleError {
+    /// The address is absent or the oracle rejected the frame.
+    Nack,
+    /// The device history is full (`MAX_BYTES`).
+    Overflow,
+}
+
+impl fmt::Display for OracleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            OracleError::Nack => f.write_str("Nack"),
+            OracleError::Overflow => f.write_str("Overflow"),
+        }
+    }
+}
+
+#[cfg(feature = "ehal_1_0")]
+impl embedded_hal_1::i2c::Error for OracleError {
+    fn kind(&self) -> embedded_hal_1::i2c::ErrorKind {
+        match self {
+            OracleError::Nack => embedded_hal_1::i2c::ErrorKind::NoAcknowledge(
+                embedded_hal_1::i2c::NoAcknowledgeSource::Data,
+            ),
+            OracleError::Overflow => embedded_hal_1::i2c::ErrorKind::Other,
+        }
+    }
+}
+
+/// A fake bus whose devices ACK or NACK writes according to a rule.
+///
+/// Each of the `DEVICES` addresses keeps the bytes of the frames it has
+/// acknowledged so far. For every non-empty write, `oracle(addr, history,
+/// frame)` decides whether the frame is acknowledged: an accepted frame is
+/// appended to the history, a rejected one NACKs and resets the history, as
+/// if the device had returned to its power-on state. Empty writes (probes)
+/// succeed on every configured address. Reads return zeros.
+///
+/// ```ignore
+/// // 0x3C accepts A0, A2, A1 in this order only.
+/// let mut i2c = OracleI2c::<_, 1, 16>::new(&[0x3C], |_, history: &[u8], frame: &[u8]| {
+///     frame[1] == [0xA0, 0xA2, 0xA1][history.len() / 2]
+/// });
+/// ```
+pub struct OracleI2c<F, const DEVICES: usize, const MAX_BYTES: usize> {
+    devices: heapless::Vec<(u8, heapless::Vec<u8, MAX_BYTES>), DEVICES>,
+    oracle: F,
+}
+
+impl<F, const DEVICES: usize, const MAX_BYTES: usize> OracleI2c<F, DEVICES, MAX_BYTES>
+where
+    F: FnMut(u8, &[u8], &[u8]) -> bool,
+{
+    /// A bus with devices at `addrs` (at most `DEVICES`; extra ones are ignored).
+    pub fn new(addrs: &[u8], oracle: F) -> Self {
+        let mut devices = heapless::Vec::new();
+        for &addr in addrs.iter().take(DEVICES) {
+            devices.push((addr, heapless::Vec::new())).ok();
+        }
+        Self { devices, oracle }
+    }
+
+    /// The bytes `addr` has acknowledged since its last reset.
+    pub fn history(&self, addr: u8) -> Option<&[u8]> {
+        self.devices
+            .iter()
+            .find(|(a, _)| *a == addr)
+            .map(|(_, history)| history.as_slice())
+    }
+}
+
+impl<F, const DEVICES: usize, const MAX_BYTES: usize> I2cCompat for OracleI2c<F, DEVICES, MAX_BYTES>
+where
+    F: FnMut(u8, &[u8], &[u8]) -> bool,
+{
+    type Error = OracleError;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        let Some((_, history)) = self.devices.iter_mut().find(|(a, _)| *a == addr) else {
+            return Err(OracleError::Nack);
+        };
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        if !(self.oracle)(addr, history, bytes) {
+            history.clear();
+            return Err(OracleError::Nack);
+        }
+        history
+            .extend_from_slice(bytes)
+            .map_err(|_| OracleError::Overflow)
+    }
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.write_read(addr, &[], buffer)
+    }
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.write(addr, bytes)?;
+        buffer.fill(0);
+        Ok(())
+    }
+
+    fn probe(&mut self, addr: u8) -> Result<bool, Self::Error> {
+        Ok(self.devices.iter().any(|(a, _)| *a == addr))
+    }
+
+    fn is_nack(&self, error: &Self::Error) -> bool {
+        matches!(error, OracleError::Nack)
+    }
+}
+
+/// Error reported by [`TraceReplayI2c`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReplayError {
+    /// The recorded write failed on the real bus.
+    Nack,
+    /// The write differs from the next record; the code under test no longer
+    /// talks to the device the way it did during the capture.
+    Diverged { index: usize },
+    /// Every record has been replayed.
+    Exhausted,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Nack => f.write_str("Nack"),
+            ReplayError::Diverged { index } => write!(f, "Diverged at record {index}"),
+            ReplayError::Exhausted => f.write_str("Exhausted"),
+        }
+    }
+}
+
+#[cfg(feature = "ehal_1_0")]
+impl embedded_hal_1::i2c::Error for ReplayError {
+    fn kind(&self) -> embedded_hal_1::i2c::ErrorKind {
+        match self {
+            ReplayError::Nack => embedded_hal_1::i2c::ErrorKind::NoAcknowledge(
+                embedded_hal_1::i2c::NoAcknowledgeSource::Data,
+            ),
+            _ => embedded_hal_1::i2c::ErrorKind::Other,
+        }
+    }
+}
+
+/// A fake bus that answers writes from a trace captured by a
+/// [`TracingExecutor`](crate::explore::trace::TracingExecutor).
+///
+/// Writes must arrive in the recorded order: each one is matched against the
+/// next record and ACKs or NACKs as the real device did. A write that does not
+/// match fails with [`ReplayError::Diverged`], so a hardware capture becomes a
+/// regression fixture for the explorer. Probes succeed on the addresses that
+/// appear in the trace; reads are not traced and return zeros.
+///
+/// ```ignore
+/// let mut i2c = TraceReplayI2c::new(CAPTURED.as_slice());
+/// let result = explorer.explore(&mut i2c, &mut executor, &mut serial)?;
+/// assert!(i2c.is_exhausted());
+/// ```
+pub struct TraceReplayI2c<'a, const MAX_BYTES: usize> {
+    trace: &'a [TraceRecord<MAX_BYTES>],
+    pos: usize,
+}
+
+impl<'a, const MAX_BYTES: usize> TraceReplayI2c<'a, MAX_BYTES> {
+    pub fn new(trace: &'a [TraceRecord<MAX_BYTES>]) -> Self {
+        Self { trace, pos: 0 }
+    }
+
+    /// Number of records replayed so far.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Whether every record has been replayed.
+    pub fn is_exhausted(&self) -> bool {
+        self.pos >= self.trace.len()
+    }
+}
+
+impl<const MAX_BYTES: usize> I2cCompat for TraceReplayI2c<'_, MAX_BYTES> {
+    type Error = ReplayError;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        let Some(record) = self.trace.get(self.pos) else {
+            return Err(ReplayError::Exhausted);
+        };
+        if record.addr != addr || record.bytes.as_slice() != bytes {
+            return Err(ReplayError::Diverged { index: self.pos });
+        }
+        self.pos += 1;
+        if record.ok {
+            Ok(())
+        } else {
+            Err(ReplayError::Nack)
+        }
+    }
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.write_read(addr, &[], buffer)
+    }
+
+    fn write_read(
+        &mut self,
+        addr: u8,
+        _bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        // Only plain writes are traced, so reads do not consume records.
+        if !self.probe(addr)? {
+            return Err(ReplayError::Nack);
+        }
+        buffer.fill(0);
+        Ok(())
+    }
+
+    fn probe(&mut self, addr: u8) -> Result<bool, Self::Error> {
+        Ok(self.trace.iter().any(|r| r.addr == addr))
+    }
+
+    fn is_nack(&self, error: &Self::Error) -> bool {
+        matches!(error, ReplayError::Nack)
+    }
+}
@@ -1,11 +1,27 @@
 //! compat/mod.rs
 pub mod adapt;
+pub mod bitbang;
+pub mod delay_compat;
 pub mod err_compat;
+pub mod gpio_compat;
+pub mod guard;
 pub mod i2c_compat;
+#[cfg(any(test, feature = "testing"))]
+pub mod mock;
 pub mod serial_compat;
+pub mod time_compat;
 pub mod util;
 
 pub use adapt::FmtWriteAdapter;
+pub use bitbang::BitbangI2c;
+pub use delay_compat::{DelayCompat, ExecutorDelay, SpinDelay};
 pub use err_compat::HalErrorExt;
+pub use gpio_compat::{InputPinCompat, OutputPinCompat};
+pub use guard::GuardedI2c;
 pub use i2c_compat::I2cCompat;
+#[cfg(feature = "ehal_async")]
+pub use i2c_compat::I2cCompatAsync;
+#[cfg(feature = "ehal_0_2")]
+pub use serial_compat::{BoundedSerial, BoundedSerialError};
 pub use serial_compat::{SerialCompat, SerialEio, UartLike};
+pub use time_compat::TimeSource;
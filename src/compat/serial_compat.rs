@@ -1,7 +1,7 @@
 //! src/compat/serial_compat.rs
 use core::fmt::Debug;
 use embedded_io;
-#[cfg(all(feature = "ehal_0_2", not(feature = "ehal_1_0")))]
+#[cfg(feature = "ehal_0_2")]
 use nb;
 /// ### Differ bus injection with blanket (SELF RESPONSIBILITY)
 /// ```ignore
@@ -61,6 +61,11 @@ where
 }
 
 // ========== ehal 0.2.x ==========
+/// Blanket impl for ehal 0.2 serial writers.
+///
+/// Each byte is sent with `nb::block!`, so a writer that keeps returning
+/// `WouldBlock` (e.g. a disconnected USB-serial bridge) blocks forever. Wrap
+/// it in [`BoundedSerial`] to give up after a bounded number of polls instead.
 #[cfg(all(feature = "ehal_0_2", not(feature = "ehal_1_0")))]
 impl<S> SerialCompat for S
 where
@@ -82,6 +87,84 @@ where
     }
 }
 
+/// Error of a [`BoundedSerial`] writer.
+#[cfg(feature = "ehal_0_2")]
+#[derive(Debug)]
+pub enum BoundedSerialError<E> {
+    /// The writer stayed busy for `max_polls` polls.
+    Timeout,
+    /// The underlying writer failed.
+    Hal(E),
+}
+
+#[cfg(feature = "ehal_0_2")]
+impl<E: Debug> embedded_io::Error for BoundedSerialError<E> {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        match self {
+            BoundedSerialError::Timeout => embedded_io::ErrorKind::TimedOut,
+            BoundedSerialError::Hal(_) => embedded_io::ErrorKind::Other,
+        }
+    }
+}
+
+/// An ehal 0.2 serial writer that gives up instead of blocking forever.
+///
+/// Every byte (and `flush`) is polled at most `max_polls` times; a writer
+/// still returning `WouldBlock` after that fails with
+/// [`BoundedSerialError::Timeout`]. Diagnostics log with `.ok()`, so a dead
+/// UART then costs a bounded delay per message instead of hanging the program.
+#[cfg(feature = "ehal_0_2")]
+pub struct BoundedSerial<S> {
+    inner: S,
+    max_polls: u32,
+}
+
+#[cfg(feature = "ehal_0_2")]
+impl<S> BoundedSerial<S> {
+    pub fn new(inner: S, max_polls: u32) -> Self {
+        Self { inner, max_polls }
+    }
+
+    /// Release the wrapped writer.
+    pub fn into_inner(self) -> S {
+        self.inner
+    }
+
+    fn poll<E>(
+        &mut self,
+        mut op: impl FnMut(&mut S) -> nb::Result<(), E>,
+    ) -> Result<(), BoundedSerialError<E>> {
+        for _ in 0..self.max_polls {
+            match op(&mut self.inner) {
+                Ok(()) => return Ok(()),
+                Err(nb::Error::WouldBlock) => core::hint::spin_loop(),
+                Err(nb::Error::Other(e)) => return Err(BoundedSerialError::Hal(e)),
+            }
+        }
+        Err(BoundedSerialError::Timeout)
+    }
+}
+
+#[cfg(feature = "ehal_0_2")]
+impl<S> SerialCompat for BoundedSerial<S>
+where
+    S: embedded_hal_0_2::serial::Write<u8>,
+    <S as embedded_hal_0_2::serial::Write<u8>>::Error: Debug,
+{
+    type Error = BoundedSerialError<<S as embedded_hal_0_2::serial::Write<u8>>::Error>;
+
+    fn write(&mut self, buf: &[u8]) -> Result<(), Self::Error> {
+        for &byte in buf {
+            self.poll(|s| embedded_hal_0_2::serial::Write::write(s, byte))?;
+        }
+        Ok(())
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.poll(embedded_hal_0_2::serial::Write::flush)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -120,6 +203,36 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "ehal_0_2")]
+    mod bounded_tests {
+        use super::*;
+
+        /// A UART whose line is disconnected: it never accepts a byte.
+        struct DeadUart;
+
+        impl embedded_hal_0_2::serial::Write<u8> for DeadUart {
+            type Error = core::convert::Infallible;
+
+            fn write(&mut self, _word: u8) -> nb::Result<(), Self::Error> {
+                Err(nb::Error::WouldBlock)
+            }
+
+            fn flush(&mut self) -> nb::Result<(), Self::Error> {
+                Err(nb::Error::WouldBlock)
+            }
+        }
+
+        #[test]
+        fn test_bounded_serial_times_out() {
+            let mut serial = BoundedSerial::new(DeadUart, 10);
+            assert!(matches!(
+                serial.write(b"hi"),
+                Err(BoundedSerialError::Timeout)
+            ));
+            assert!(matches!(serial.flush(), Err(BoundedSerialError::Timeout)));
+        }
+    }
+
     // ===== 0.2 Dummy UART =====
     #[cfg(all(feature = "ehal_0_2", not(feature = "ehal_1_0")))]
     mod ehal_0_2_tests {
@@ -148,9 +261,9 @@ mod tests {
             let buf = b"hello";
 
             for &b in buf {
-                assert!(nb::block!(uart.write(b)).is_ok());
+                assert!(nb::block!(HalWrite::write(&mut uart, b)).is_ok());
             }
-            assert!(nb::block!(uart.flush()).is_ok());
+            assert!(nb::block!(HalWrite::flush(&mut uart)).is_ok());
         }
     }
 }
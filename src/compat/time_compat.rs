@@ -0,0 +1,21 @@
+//! src/compat/time_compat.rs
+//! Monotonic time source used by timing-aware diagnostics.
+
+/// A free-running tick counter (CPU cycles, microseconds, milliseconds...).
+///
+/// The unit is up to the implementor; results are reported in the same ticks.
+/// Counters are expected to wrap, so elapsed time is always computed with
+/// `wrapping_sub`. This is the same shape `measure_cycles!` expects.
+pub trait TimeSource {
+    fn now(&self) -> u32;
+}
+
+/// Any `Fn() -> u32` closure can serve as a time source.
+impl<F> TimeSource for F
+where
+    F: Fn() -> u32,
+{
+    fn now(&self) -> u32 {
+        self()
+    }
+}
@@ -2,30 +2,41 @@
 
 use crate::error::BitFlagsError;
 
-/// A bitflag structure optimized for 128 bits, used for tracking I2C addresses.
+/// A fixed-size set of bit flags backed by `BYTES` bytes (`BYTES * 8` bits).
+///
+/// `BitFlagsN` is `Copy` and `Eq`: flag sets are snapshotted by plain
+/// assignment and can be compared directly, e.g. to check whether a pruning
+/// pass changed anything. Use a small `BYTES` to track a few command nodes
+/// without paying for the full address space.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-pub struct BitFlags {
-    bytes: [u8; 16],
+pub struct BitFlagsN<const BYTES: usize> {
+    bytes: [u8; BYTES],
 }
 
-impl Default for BitFlags {
+/// 128 flags, one per 7-bit I2C address; the set used throughout the crate.
+pub type BitFlags = BitFlagsN<16>;
+
+impl<const BYTES: usize> Default for BitFlagsN<BYTES> {
     fn default() -> Self {
         Self::new()
     }
 }
 
-impl BitFlags {
+impl<const BYTES: usize> BitFlagsN<BYTES> {
     pub const fn new() -> Self {
-        Self { bytes: [0u8; 16] }
+        Self {
+            bytes: [0u8; BYTES],
+        }
     }
 
-    const N_BITS: usize = 128;
+    /// Number of flags in the set.
+    pub const N_BITS: usize = BYTES * 8;
 
     fn check_bounds(&self, idx: usize) -> Result<(), BitFlagsError> {
         if idx >= Self::N_BITS {
             Err(BitFlagsError::IndexOutOfBounds {
                 idx,
-                max: Self::N_BITS - 1,
+                max: Self::N_BITS.saturating_sub(1),
             })
         } else {
             Ok(())
@@ -62,9 +73,73 @@ impl BitFlags {
     pub fn clear_all(&mut self) {
         self.bytes.fill(0);
     }
+
+    /// Bits set in `self` but not in `other`.
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut out = *self;
+        for (b, o) in out.bytes.iter_mut().zip(other.bytes.iter()) {
+            *b &= !o;
+        }
+        out
+    }
+
+    /// Index of the lowest set bit, or `None` if no bit is set.
+    pub fn first_set(&self) -> Option<usize> {
+        self.next_set(0)
+    }
+
+    /// Index of the lowest clear bit at or after `from`, or `None` if every
+    /// bit from there on is set. Whole bytes of set bits are skipped at once.
+    pub fn first_clear(&self, from: usize) -> Option<usize> {
+        self.find_from(from, |b| !b)
+    }
+
+    /// Number of set bits.
+    pub fn count_ones(&self) -> u32 {
+        self.bytes.iter().map(|b| b.count_ones()).sum()
+    }
+
+    /// Indices of the set bits, in ascending order. Zero bytes are skipped
+    /// whole.
+    pub fn iter_set(&self) -> impl Iterator<Item = usize> + '_ {
+        core::iter::successors(self.first_set(), move |&idx| self.next_set(idx + 1))
+    }
+
+    fn next_set(&self, from: usize) -> Option<usize> {
+        self.find_from(from, |b| b)
+    }
+
+    /// Lowest index `>= from` whose bit is set in `map(byte)`.
+    fn find_from(&self, from: usize, map: impl Fn(u8) -> u8) -> Option<usize> {
+        if from >= Self::N_BITS {
+            return None;
+        }
+        let mut byte = from / 8;
+        // Mask off the bits below `from` in the first byte.
+        let mut word = map(self.bytes[byte]) & (0xFF << (from % 8));
+        while word == 0 {
+            byte += 1;
+            word = map(*self.bytes.get(byte)?);
+        }
+        Some(byte * 8 + word.trailing_zeros() as usize)
+    }
 }
 
-impl core::ops::BitOrAssign for BitFlags {
+impl BitFlags {
+    /// Flags from a `u128`, bit `n` of the integer being flag `n`.
+    pub const fn from_u128(bits: u128) -> Self {
+        Self {
+            bytes: bits.to_le_bytes(),
+        }
+    }
+
+    /// The flags as a `u128`, flag `n` being bit `n` of the integer.
+    pub const fn to_u128(&self) -> u128 {
+        u128::from_le_bytes(self.bytes)
+    }
+}
+
+impl<const BYTES: usize> core::ops::BitOrAssign for BitFlagsN<BYTES> {
     fn bitor_assign(&mut self, rhs: Self) {
         for i in 0..self.bytes.len() {
             self.bytes[i] |= rhs.bytes[i];
@@ -72,6 +147,43 @@ impl core::ops::BitOrAssign for BitFlags {
     }
 }
 
+impl<const BYTES: usize> core::ops::BitAndAssign for BitFlagsN<BYTES> {
+    fn bitand_assign(&mut self, rhs: Self) {
+        for i in 0..self.bytes.len() {
+            self.bytes[i] &= rhs.bytes[i];
+        }
+    }
+}
+
+impl<const BYTES: usize> core::ops::BitOr for BitFlagsN<BYTES> {
+    type Output = Self;
+
+    fn bitor(mut self, rhs: Self) -> Self {
+        self |= rhs;
+        self
+    }
+}
+
+impl<const BYTES: usize> core::ops::BitAnd for BitFlagsN<BYTES> {
+    type Output = Self;
+
+    fn bitand(mut self, rhs: Self) -> Self {
+        self &= rhs;
+        self
+    }
+}
+
+impl<const BYTES: usize> core::ops::Not for BitFlagsN<BYTES> {
+    type Output = Self;
+
+    fn not(mut self) -> Self {
+        for b in self.bytes.iter_mut() {
+            *b = !*b;
+        }
+        self
+    }
+}
+
 //---
 // ## Hexadecimal Utilities
 // Functions for writing bytes in hexadecimal format to a stream.
@@ -84,7 +196,7 @@ fn nibble_to_hex(n: u8) -> char {
     }
 }
 
-pub fn write_byte_hex_fmt<W: core::fmt::Write>(w: &mut W, byte: u8) -> core::fmt::Result {
+pub fn write_byte_hex_fmt<W: core::fmt::Write + ?Sized>(w: &mut W, byte: u8) -> core::fmt::Result {
     let hi = byte >> 4;
     let lo = byte & 0x0F;
     w.write_char(nibble_to_hex(hi))?;
@@ -92,7 +204,10 @@ pub fn write_byte_hex_fmt<W: core::fmt::Write>(w: &mut W, byte: u8) -> core::fmt
     Ok(())
 }
 
-pub fn write_bytes_hex_fmt<W: core::fmt::Write>(w: &mut W, bytes: &[u8]) -> core::fmt::Result {
+pub fn write_bytes_hex_fmt<W: core::fmt::Write + ?Sized>(
+    w: &mut W,
+    bytes: &[u8],
+) -> core::fmt::Result {
     for (i, &b) in bytes.iter().enumerate() {
         write_byte_hex_fmt(w, b)?;
         if i != bytes.len() - 1 {
@@ -116,6 +231,36 @@ pub fn write_bytes_hex_prefixed_fmt<W: core::fmt::Write>(
     Ok(())
 }
 
+//---
+// ## Multi-byte Register Utilities
+// Helpers for 16-bit register values split across two bytes.
+
+/// Writes `value` as two hex bytes, most significant first (`0x1234` -> `12 34`).
+pub fn write_u16_hex_be<W: core::fmt::Write>(w: &mut W, value: u16) -> core::fmt::Result {
+    write_bytes_hex_fmt(w, &value.to_be_bytes())
+}
+
+/// Writes `value` as two hex bytes, least significant first (`0x1234` -> `34 12`).
+pub fn write_u16_hex_le<W: core::fmt::Write>(w: &mut W, value: u16) -> core::fmt::Result {
+    write_bytes_hex_fmt(w, &value.to_le_bytes())
+}
+
+/// Reads a big-endian `u16` from the first two bytes, or `None` if there are fewer.
+pub fn read_u16_be(bytes: &[u8]) -> Option<u16> {
+    match bytes {
+        [hi, lo, ..] => Some(u16::from_be_bytes([*hi, *lo])),
+        _ => None,
+    }
+}
+
+/// Reads a little-endian `u16` from the first two bytes, or `None` if there are fewer.
+pub fn read_u16_le(bytes: &[u8]) -> Option<u16> {
+    match bytes {
+        [lo, hi, ..] => Some(u16::from_le_bytes([*lo, *hi])),
+        _ => None,
+    }
+}
+
 //---
 // ## String and Character Utilities
 // Functions for writing strings and handling character encodings.
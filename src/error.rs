@@ -2,6 +2,7 @@ use core::fmt;
 
 /// Defines the category of an error.
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ErrorKind {
     /// Errors related to the UART peripheral.
     Uart(UartError),
@@ -26,6 +27,7 @@ pub enum ErrorKind {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum UartError {
     /// A framing error occurred.
     Framing,
@@ -52,6 +54,7 @@ impl fmt::Display for UartError {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum I2cError {
     /// A NACK (No Acknowledgment) was received from a device.
     Nack,
@@ -72,6 +75,7 @@ impl fmt::Display for I2cError {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum SpiError {
     /// A mode fault occurred on the SPI bus.
     ModeFault,
@@ -86,6 +90,7 @@ impl fmt::Display for SpiError {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum GpioError {
     /// An invalid state was detected for a GPIO pin.
     InvalidState,
@@ -100,6 +105,7 @@ impl fmt::Display for GpioError {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum AdcError {
     /// The ADC reading is out of its valid range.
     OutOfRange,
@@ -114,6 +120,7 @@ impl fmt::Display for AdcError {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum HardwareError {
     /// A power fault was detected.
     Power,
@@ -137,6 +144,7 @@ impl fmt::Display for HardwareError {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum BufferError {
     /// A buffer overflow occurred.
     Overflow,
@@ -170,8 +178,131 @@ impl fmt::Display for ErrorKind {
     }
 }
 
+impl ErrorKind {
+    /// A stable 16-bit code for the error, for links too narrow for the
+    /// `Display` string.
+    ///
+    /// The high byte is the category, the low byte the subvariant in
+    /// declaration order:
+    ///
+    /// | Code     | Error                                                        |
+    /// | -------- | ------------------------------------------------------------ |
+    /// | `0x00nn` | `InvalidConfig` (01), `Unknown` (02), `Other` (03)           |
+    /// | `0x01nn` | `Uart`: Framing, Parity, Overrun, Underrun, Timeout (00..04) |
+    /// | `0x02nn` | `I2c`: Nack, ArbitrationLost, Bus (00..02)                   |
+    /// | `0x03nn` | `Spi`: ModeFault (00)                                        |
+    /// | `0x04nn` | `Gpio`: InvalidState (00)                                    |
+    /// | `0x05nn` | `Adc`: OutOfRange (00)                                       |
+    /// | `0x06nn` | `Hardware`: Power, Peripheral, ShortCircuit, OpenCircuit (00..03) |
+    /// | `0x07nn` | `Buffer`: Overflow, Underflow (00..01)                       |
+    ///
+    /// Existing codes never change; new variants get new codes.
+    pub const fn as_code(&self) -> u16 {
+        let (category, sub): (u8, u8) = match self {
+            ErrorKind::InvalidConfig => (0x00, 0x01),
+            ErrorKind::Unknown => (0x00, 0x02),
+            ErrorKind::Other => (0x00, 0x03),
+            ErrorKind::Uart(e) => (
+                0x01,
+                match e {
+                    UartError::Framing => 0x00,
+                    UartError::Parity => 0x01,
+                    UartError::Overrun => 0x02,
+                    UartError::Underrun => 0x03,
+                    UartError::Timeout => 0x04,
+                },
+            ),
+            ErrorKind::I2c(e) => (
+                0x02,
+                match e {
+                    I2cError::Nack => 0x00,
+                    I2cError::ArbitrationLost => 0x01,
+                    I2cError::Bus => 0x02,
+                },
+            ),
+            ErrorKind::Spi(SpiError::ModeFault) => (0x03, 0x00),
+            ErrorKind::Gpio(GpioError::InvalidState) => (0x04, 0x00),
+            ErrorKind::Adc(AdcError::OutOfRange) => (0x05, 0x00),
+            ErrorKind::Hardware(e) => (
+                0x06,
+                match e {
+                    HardwareError::Power => 0x00,
+                    HardwareError::Peripheral => 0x01,
+                    HardwareError::ShortCircuit => 0x02,
+                    HardwareError::OpenCircuit => 0x03,
+                },
+            ),
+            ErrorKind::Buffer(e) => (
+                0x07,
+                match e {
+                    BufferError::Overflow => 0x00,
+                    BufferError::Underflow => 0x01,
+                },
+            ),
+        };
+        u16::from_be_bytes([category, sub])
+    }
+
+    /// The error for a code produced by [`ErrorKind::as_code`], or `None`
+    /// for an unassigned code.
+    pub const fn from_code(code: u16) -> Option<ErrorKind> {
+        let [category, sub] = code.to_be_bytes();
+        let kind = match (category, sub) {
+            (0x00, 0x01) => ErrorKind::InvalidConfig,
+            (0x00, 0x02) => ErrorKind::Unknown,
+            (0x00, 0x03) => ErrorKind::Other,
+            (0x01, 0x00) => ErrorKind::Uart(UartError::Framing),
+            (0x01, 0x01) => ErrorKind::Uart(UartError::Parity),
+            (0x01, 0x02) => ErrorKind::Uart(UartError::Overrun),
+            (0x01, 0x03) => ErrorKind::Uart(UartError::Underrun),
+            (0x01, 0x04) => ErrorKind::Uart(UartError::Timeout),
+            (0x02, 0x00) => ErrorKind::I2c(I2cError::Nack),
+            (0x02, 0x01) => ErrorKind::I2c(I2cError::ArbitrationLost),
+            (0x02, 0x02) => ErrorKind::I2c(I2cError::Bus),
+            (0x03, 0x00) => ErrorKind::Spi(SpiError::ModeFault),
+            (0x04, 0x00) => ErrorKind::Gpio(GpioError::InvalidState),
+            (0x05, 0x00) => ErrorKind::Adc(AdcError::OutOfRange),
+            (0x06, 0x00) => ErrorKind::Hardware(HardwareError::Power),
+            (0x06, 0x01) => ErrorKind::Hardware(HardwareError::Peripheral),
+            (0x06, 0x02) => ErrorKind::Hardware(HardwareError::ShortCircuit),
+            (0x06, 0x03) => ErrorKind::Hardware(HardwareError::OpenCircuit),
+            (0x07, 0x00) => ErrorKind::Buffer(BufferError::Overflow),
+            (0x07, 0x01) => ErrorKind::Buffer(BufferError::Underflow),
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// An [`ErrorKind`] paired with the I2C address it came from.
+///
+/// Returned by [`HalErrorExt::to_compat_at`](crate::compat::HalErrorExt::to_compat_at)
+/// so logs can say "Nack at 0x3C" while `kind` still compares equal to the
+/// plain `ErrorKind::I2c(..)` that `to_compat` returns.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct AddressedError {
+    /// The unified error kind.
+    pub kind: ErrorKind,
+    /// The 7-bit address of the device that failed.
+    pub addr: u8,
+}
+
+impl fmt::Display for AddressedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at 0x{:02X}", self.kind, self.addr)
+    }
+}
+
+impl From<AddressedError> for ErrorKind {
+    fn from(e: AddressedError) -> Self {
+        e.kind
+    }
+}
+
 /// Errors that can occur within the BitFlags utility.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum BitFlagsError {
     /// An index is out of bounds for the bit flags.
     IndexOutOfBounds { idx: usize, max: usize },
@@ -190,8 +321,28 @@ impl fmt::Display for BitFlagsError {
     }
 }
 
+/// Errors reported by the bit-banged I2C backend.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BitbangError {
+    /// The target did not acknowledge an address or data byte.
+    Nack,
+    /// Driving or sampling one of the GPIO lines failed.
+    Pin,
+}
+
+impl fmt::Display for BitbangError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BitbangError::Nack => f.write_str("Nack"),
+            BitbangError::Pin => f.write_str("Pin"),
+        }
+    }
+}
+
 /// Errors that can occur during the exploration of command sequences.
 #[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ExplorerError {
     /// The provided sequence contained more commands than supported by the capacity.
     TooManyCommands,
@@ -203,12 +354,53 @@ pub enum ExplorerError {
     ExecutionFailed(ErrorKind),
     /// An internal buffer overflowed during the exploration process.
     BufferOverflow,
+    /// A command (including its prefix) does not fit in the command buffer.
+    /// `index` is the node index, when the runner knows it.
+    CommandTooLarge {
+        index: Option<usize>,
+        required: usize,
+        available: usize,
+    },
     /// A dependency index is out of bounds.
     InvalidDependencyIndex,
     /// An I2C device was not found during a scan operation.
     DeviceNotFound(ErrorKind),
     /// An error occurred in the BitFlags utility.
     BitFlags(BitFlagsError),
+    /// The exploration used up its write budget.
+    WriteBudgetExhausted,
+    /// The run was stopped by its abort flag.
+    Aborted,
+}
+
+impl ExplorerError {
+    /// The underlying bus error of `ExecutionFailed` and `DeviceNotFound`,
+    /// e.g. to tell a NACK from a bus fault. `None` for every other variant.
+    pub fn inner_kind(&self) -> Option<ErrorKind> {
+        match self {
+            ExplorerError::ExecutionFailed(kind) | ExplorerError::DeviceNotFound(kind) => {
+                Some(*kind)
+            }
+            _ => None,
+        }
+    }
+
+    /// Records the node index in a `CommandTooLarge` error; every other
+    /// variant is returned unchanged.
+    pub fn with_command_index(self, index: usize) -> Self {
+        match self {
+            ExplorerError::CommandTooLarge {
+                required,
+                available,
+                ..
+            } => ExplorerError::CommandTooLarge {
+                index: Some(index),
+                required,
+                available,
+            },
+            other => other,
+        }
+    }
 }
 
 impl fmt::Display for ExplorerError {
@@ -219,15 +411,34 @@ impl fmt::Display for ExplorerError {
             ExplorerError::NoValidAddressesFound => f.write_str("NoValidAddressesFound"),
             ExplorerError::ExecutionFailed(kind) => write!(f, "ExecutionFailed: {kind}"),
             ExplorerError::BufferOverflow => f.write_str("BufferOverflow"),
+            ExplorerError::CommandTooLarge {
+                index: Some(index),
+                required,
+                available,
+            } => write!(
+                f,
+                "CommandTooLarge: command {index} needs {required} bytes, buffer holds {available}"
+            ),
+            ExplorerError::CommandTooLarge {
+                index: None,
+                required,
+                available,
+            } => write!(
+                f,
+                "CommandTooLarge: need {required} bytes, buffer holds {available}"
+            ),
             ExplorerError::InvalidDependencyIndex => f.write_str("InvalidDependencyIndex"),
             ExplorerError::DeviceNotFound(kind) => write!(f, "DeviceNotFound: {kind}"),
             ExplorerError::BitFlags(e) => write!(f, "BitFlagsError: {e}"),
+            ExplorerError::WriteBudgetExhausted => f.write_str("WriteBudgetExhausted"),
+            ExplorerError::Aborted => f.write_str("Aborted"),
         }
     }
 }
 
 /// Errors that can occur during command execution.
 #[derive(PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum ExecutorError {
     /// A command failed to execute due to an I2C error.
     I2cError(ErrorKind),
@@ -235,6 +446,8 @@ pub enum ExecutorError {
     ExecFailed,
     /// An internal buffer overflowed during command preparation.
     BufferOverflow,
+    /// A command (including its prefix) does not fit in the command buffer.
+    CommandTooLarge { required: usize, available: usize },
     /// An error occurred in the BitFlags utility.
     BitFlags(BitFlagsError),
     /// An error occurred in the explorer module.
@@ -248,6 +461,14 @@ impl From<ExecutorError> for ExplorerError {
             ExecutorError::I2cError(kind) => ExplorerError::ExecutionFailed(kind),
             ExecutorError::ExecFailed => ExplorerError::ExecutionFailed(ErrorKind::Unknown),
             ExecutorError::BufferOverflow => ExplorerError::BufferOverflow,
+            ExecutorError::CommandTooLarge {
+                required,
+                available,
+            } => ExplorerError::CommandTooLarge {
+                index: None,
+                required,
+                available,
+            },
             ExecutorError::BitFlags(e) => ExplorerError::BitFlags(e),
             ExecutorError::Explorer(e) => e,
         }
@@ -267,6 +488,13 @@ impl fmt::Display for ExecutorError {
             ExecutorError::I2cError(kind) => write!(f, "I2cError: {kind}"),
             ExecutorError::ExecFailed => f.write_str("ExecFailed"),
             ExecutorError::BufferOverflow => f.write_str("BufferOverflow"),
+            ExecutorError::CommandTooLarge {
+                required,
+                available,
+            } => write!(
+                f,
+                "CommandTooLarge: need {required} bytes, buffer holds {available}"
+            ),
             ExecutorError::BitFlags(e) => write!(f, "BitFlagsError: {e}"),
             ExecutorError::Explorer(e) => write!(f, "ExplorerError: {e}"),
         }
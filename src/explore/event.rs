@@ -0,0 +1,90 @@
+// event.rs
+
+use crate::error::ExecutorError;
+
+/// What happened during an exploration run, independent of how it is rendered.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Event<'a> {
+    /// A bus scan started, writing `ctrl_byte` to each address.
+    Scanning { ctrl_byte: u8 },
+    /// A device acknowledged at this address.
+    Found(u8),
+    /// Command `idx` was written to `addr` successfully.
+    CmdOk { addr: u8, idx: usize },
+    /// Command `idx` failed on `addr`.
+    CmdFail {
+        addr: u8,
+        idx: usize,
+        err: &'a ExecutorError,
+    },
+    /// The dependency graph contains a cycle.
+    Cycle,
+    /// Every command of the sequence was written to `addr`.
+    Done(u8),
+    /// Free-form diagnostic text, e.g. from a command executor.
+    Log(&'a str),
+}
+
+/// Receives the [`Event`]s of a run (see [`crate::explore::runner::run_with_events`]).
+///
+/// Closures taking an `Event` implement this trait, so ad-hoc sinks need no
+/// extra type:
+///
+/// ```ignore
+/// let mut ok = 0;
+/// run_with_events(explorer, &mut i2c, &mut executor, &mut |e: Event<'_>| {
+///     if let Event::CmdOk { .. } = e { ok += 1 }
+/// }, PREFIX)?;
+/// ```
+pub trait EventSink {
+    fn on_event(&mut self, event: Event<'_>);
+}
+
+impl<F: FnMut(Event<'_>)> EventSink for F {
+    fn on_event(&mut self, event: Event<'_>) {
+        self(event)
+    }
+}
+
+/// Default sink: renders events as the crate's usual text log.
+pub struct TextSink<'a, W: core::fmt::Write> {
+    inner: &'a mut W,
+}
+
+impl<'a, W: core::fmt::Write> TextSink<'a, W> {
+    pub fn new(inner: &'a mut W) -> Self {
+        Self { inner }
+    }
+}
+
+impl<W: core::fmt::Write> EventSink for TextSink<'_, W> {
+    fn on_event(&mut self, event: Event<'_>) {
+        let w = &mut *self.inner;
+        match event {
+            Event::Scanning { ctrl_byte } => {
+                dvcdbg_log!(w, "Scanning I2C bus with a {ctrl_byte:02X} ...\r\n")
+            }
+            Event::Found(addr) => dvcdbg_log!(w, "Found device @ {addr:02X}\r\n"),
+            Event::CmdOk { idx, .. } => dvcdbg_log!(w, "[E] OK {idx}\r\n"),
+            Event::CmdFail { idx, err, .. } => dvcdbg_log!(w, "[E] FAIL {idx}: {err}\r\n"),
+            Event::Cycle => dvcdbg_log!(w, "[error] Dependency cycle detected!\r\n"),
+            Event::Done(addr) => dvcdbg_log!(
+                w,
+                "[explorer] Single sequence execution complete for {addr:02X}.\r\n"
+            ),
+            Event::Log(text) => dvcdbg_log!(w, "{text}"),
+        };
+    }
+}
+
+/// Forwards text written by executors to a sink as [`Event::Log`].
+pub(crate) struct EventWriter<'a, K: EventSink> {
+    pub(crate) sink: &'a mut K,
+}
+
+impl<K: EventSink> core::fmt::Write for EventWriter<'_, K> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.sink.on_event(Event::Log(s));
+        Ok(())
+    }
+}
@@ -1,15 +1,126 @@
 // explorer.rs
 
+use crate::compat::delay_compat::{DelayCompat, ExecutorDelay, SpinDelay};
 use crate::compat::err_compat::HalErrorExt;
 use crate::compat::util;
-use crate::error::{ExecutorError, ExplorerError};
+use crate::error::{BitFlagsError, ExecutorError, ExplorerError};
+use core::sync::atomic::{AtomicBool, Ordering};
 
-const I2C_ADDRESS_COUNT: usize = 128;
+/// Size of the 7-bit I2C address space; the default capacity of [`ExploreResult`].
+pub const I2C_ADDRESS_COUNT: usize = 128;
 
+/// Order count above which [`Explorer::estimate_runtime`] stops counting.
+pub const ESTIMATE_ORDER_LIMIT: u64 = 100_000;
+
+/// Where a node may appear in an order, on top of its dependencies.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Placement {
+    /// Anywhere its dependencies allow.
+    #[default]
+    Any,
+    /// Before every `Any` and `Last` node.
+    First,
+    /// After every `First` and `Any` node (e.g. DISPLAY_ON).
+    Last,
+}
+
+/// Index of a node in a dependency list.
+///
+/// 16 bits wide, so generated command tables can refer to nodes past 255.
+/// This is independent of the node count limit: node sets are tracked in a
+/// 128-bit [`BitFlags`](util::BitFlags), so an explorer still takes at most
+/// `N <= 128` nodes, and indices beyond that fail the bounds check.
+pub type DepIndex = u16;
+
+/// One command of the graph and the indices of the commands it depends on.
+///
+/// Nodes are usually `static`, i.e. `CmdNode<'static>`; sequences built at
+/// runtime borrow their bytes from [`OwnedCmdNode`]s instead.
+///
+/// Build nodes with [`CmdNode::new`] and the `const` builders, or with
+/// [`nodes!`](crate::nodes); a `CmdNode { .. }` literal breaks whenever a
+/// field is added.
 #[derive(Copy, Clone)]
-pub struct CmdNode {
-    pub bytes: &'static [u8],
-    pub deps: &'static [u8],
+pub struct CmdNode<'a> {
+    pub bytes: &'a [u8],
+    pub deps: &'a [DepIndex],
+    pub placement: Placement,
+    /// Pause after the command is written successfully, in microseconds of
+    /// the executor's delay (see [`CmdExecutor::pause_us`]).
+    pub delay_us: u32,
+}
+
+impl<'a> CmdNode<'a> {
+    pub const fn new(bytes: &'a [u8], deps: &'a [DepIndex]) -> Self {
+        Self {
+            bytes,
+            deps,
+            placement: Placement::Any,
+            delay_us: 0,
+        }
+    }
+
+    /// Pauses `us` microseconds after the command, e.g. after a reset.
+    pub const fn with_delay_us(mut self, us: u32) -> Self {
+        self.delay_us = us;
+        self
+    }
+
+    /// Pins the node before all other nodes, without wiring dependencies.
+    pub const fn first(mut self) -> Self {
+        self.placement = Placement::First;
+        self
+    }
+
+    /// Pins the node after all other nodes, without wiring dependencies.
+    pub const fn last(mut self) -> Self {
+        self.placement = Placement::Last;
+        self
+    }
+}
+
+/// Tracks how many `First` and `Any` nodes are still unplaced, to enforce
+/// [`Placement`] on top of the dependency order.
+#[derive(Clone, Copy, Default)]
+struct PlacementGate {
+    first_left: usize,
+    any_left: usize,
+}
+
+impl PlacementGate {
+    fn new(nodes: &[CmdNode], skipped: impl Fn(usize) -> bool) -> Self {
+        let mut gate = Self::default();
+        for (i, node) in nodes.iter().enumerate() {
+            if !skipped(i) {
+                gate.give_back(node.placement);
+            }
+        }
+        gate
+    }
+
+    fn allows(&self, placement: Placement) -> bool {
+        match placement {
+            Placement::First => true,
+            Placement::Any => self.first_left == 0,
+            Placement::Last => self.first_left == 0 && self.any_left == 0,
+        }
+    }
+
+    fn take(&mut self, placement: Placement) {
+        match placement {
+            Placement::First => self.first_left -= 1,
+            Placement::Any => self.any_left -= 1,
+            Placement::Last => {}
+        }
+    }
+
+    fn give_back(&mut self, placement: Placement) {
+        match placement {
+            Placement::First => self.first_left += 1,
+            Placement::Any => self.any_left += 1,
+            Placement::Last => {}
+        }
+    }
 }
 
 pub trait CmdExecutor<I2C, const CMD_BUFFER_SIZE: usize> {
@@ -21,16 +132,26 @@ pub trait CmdExecutor<I2C, const CMD_BUFFER_SIZE: usize> {
         cmd: &[u8],
         writer: &mut W,
     ) -> Result<(), ExecutorError>;
+
+    /// Pauses after a node with a non-zero [`CmdNode::delay_us`] was executed.
+    /// Executors without a delay source ignore it.
+    fn pause_us(&mut self, _us: u32) {}
 }
 
 /// A stateful iterator for generating a single topological sort using Kahn's algorithm.
 /// This avoids allocating the entire sorted sequence in memory at once.
+///
+/// Ready nodes are taken last-in first-out by default. [`TopologicalIter::fifo`]
+/// takes them first-in first-out instead, which follows the declaration order
+/// of the nodes wherever the dependencies allow.
 pub struct TopologicalIter<'a, const N: usize, const MAX_DEPS_TOTAL: usize> {
-    nodes: &'a [CmdNode],
+    nodes: &'a [CmdNode<'a>],
+    fifo: bool,
     in_degree: [u8; N],
-    adj_list_rev_flat: [u8; MAX_DEPS_TOTAL],
+    adj_list_rev_flat: [DepIndex; MAX_DEPS_TOTAL],
     adj_list_rev_offsets: [u16; N],
     queue: heapless::Vec<u8, N>,
+    gate: PlacementGate,
     visited_count: usize,
     total_non_failed: usize,
     deps_total_len: usize,
@@ -46,25 +167,59 @@ impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> TopologicalIter<'a, N, MAX
         explorer: &'a Explorer<N, MAX_DEPS_TOTAL>,
         failed_nodes: &util::BitFlags,
     ) -> Result<Self, ExplorerError> {
-        let len = explorer.nodes.len();
+        Self::from_nodes(explorer.nodes, failed_nodes, &util::BitFlags::new())
+    }
+
+    /// Same as [`TopologicalIter::new`], taking ready nodes in FIFO order.
+    pub fn new_fifo(
+        explorer: &'a Explorer<N, MAX_DEPS_TOTAL>,
+        failed_nodes: &util::BitFlags,
+    ) -> Result<Self, ExplorerError> {
+        Self::new(explorer, failed_nodes).map(Self::fifo)
+    }
+
+    /// Takes ready nodes first-in first-out, for a reproducible order that
+    /// matches the declaration order when the dependencies allow it.
+    pub fn fifo(mut self) -> Self {
+        self.fifo = true;
+        self
+    }
+
+    /// Builds the iterator over `nodes`. Failed nodes are skipped and block
+    /// their dependents; disabled nodes are skipped and count as satisfied
+    /// dependencies. A [`Placement`] that contradicts the dependencies (a
+    /// `First` node depending on an `Any` one) is reported as a cycle.
+    pub(crate) fn from_nodes(
+        nodes: &'a [CmdNode],
+        failed_nodes: &util::BitFlags,
+        disabled_nodes: &util::BitFlags,
+    ) -> Result<Self, ExplorerError> {
+        let len = nodes.len();
         if len > N {
             return Err(ExplorerError::TooManyCommands);
         }
+        let skipped = |i: usize| {
+            failed_nodes.get(i).unwrap_or(false) || disabled_nodes.get(i).unwrap_or(false)
+        };
+        let is_disabled = |i: usize| disabled_nodes.get(i).unwrap_or(false);
 
         let mut in_degree: [u8; N] = [0; N];
-        let mut adj_list_rev_flat: [u8; MAX_DEPS_TOTAL] = [0; MAX_DEPS_TOTAL];
+        let mut adj_list_rev_flat: [DepIndex; MAX_DEPS_TOTAL] = [0; MAX_DEPS_TOTAL];
         let mut rev_adj_offsets: [u16; N] = [0; N];
         let mut total_non_failed = 0;
 
         // Pass 1: Count dependencies and in-degrees
-        for (i, node) in explorer.nodes.iter().enumerate().take(len) {
-            if !failed_nodes.get(i).unwrap_or(false) {
+        for (i, node) in nodes.iter().enumerate().take(len) {
+            if !skipped(i) {
                 total_non_failed += 1;
                 for &dep_idx in node.deps.iter() {
                     let dep_idx_usize = dep_idx as usize;
                     if dep_idx_usize >= len {
                         return Err(ExplorerError::InvalidDependencyIndex);
                     }
+                    if is_disabled(dep_idx_usize) {
+                        continue;
+                    }
                     in_degree[i] = in_degree[i].saturating_add(1);
                     rev_adj_offsets[dep_idx_usize] =
                         rev_adj_offsets[dep_idx_usize].saturating_add(1);
@@ -86,21 +241,24 @@ impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> TopologicalIter<'a, N, MAX
 
         // Re-use `rev_adj_offsets` as write pointers
         let mut write_pointers = rev_adj_offsets;
-        for (i, node) in explorer.nodes.iter().enumerate().take(len) {
-            if failed_nodes.get(i).unwrap_or(false) {
+        for (i, node) in nodes.iter().enumerate().take(len) {
+            if skipped(i) {
                 continue;
             }
             for &dep_idx in node.deps.iter() {
                 let dep_idx_usize = dep_idx as usize;
+                if is_disabled(dep_idx_usize) {
+                    continue;
+                }
                 let write_pos = write_pointers[dep_idx_usize] as usize;
-                adj_list_rev_flat[write_pos] = i as u8; // Store 'i' as a node that depends on 'dep_idx_usize'
+                adj_list_rev_flat[write_pos] = i as DepIndex; // Store 'i' as a node that depends on 'dep_idx_usize'
                 write_pointers[dep_idx_usize] = write_pointers[dep_idx_usize].saturating_add(1);
             }
         }
 
         let mut queue: heapless::Vec<u8, N> = heapless::Vec::new();
         for (i, &degree) in in_degree.iter().enumerate().take(len) {
-            if degree == 0 && !failed_nodes.get(i).unwrap_or(false) {
+            if degree == 0 && !skipped(i) {
                 queue
                     .push(i as u8)
                     .map_err(|_| ExplorerError::BufferOverflow)?;
@@ -108,11 +266,13 @@ impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> TopologicalIter<'a, N, MAX
         }
 
         Ok(Self {
-            nodes: explorer.nodes,
+            nodes,
+            fifo: false,
             in_degree,
             adj_list_rev_flat,
             adj_list_rev_offsets: rev_adj_offsets, // Use the final offsets
             queue,
+            gate: PlacementGate::new(nodes, skipped),
             visited_count: 0,
             total_non_failed,
             deps_total_len,
@@ -123,6 +283,23 @@ impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> TopologicalIter<'a, N, MAX
     pub fn is_cycle_detected(&self) -> bool {
         self.visited_count != self.total_non_failed
     }
+
+    /// After iteration, the nodes that could not be placed: those still
+    /// waiting on a dependency (nonzero in-degree) and those held back by a
+    /// contradictory [`Placement`]. These are the nodes in or downstream of
+    /// the cycle; the set is empty when [`Self::is_cycle_detected`] is false.
+    pub fn cycle_nodes(&self) -> util::BitFlags {
+        let mut stuck = util::BitFlags::new();
+        for (i, &degree) in self.in_degree.iter().enumerate().take(self.nodes.len()) {
+            if degree > 0 {
+                stuck.set(i).ok();
+            }
+        }
+        for &i in self.queue.iter() {
+            stuck.set(i as usize).ok();
+        }
+        stuck
+    }
 }
 
 impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> Iterator
@@ -131,11 +308,16 @@ impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> Iterator
     type Item = usize; // Return the index of the next node
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.queue.is_empty() {
-            return None;
-        }
-
-        let u = self.queue.pop()? as usize;
+        // Latest ready node first (LIFO) or earliest (FIFO), unless its
+        // placement must wait.
+        let allowed = |v: &u8| self.gate.allows(self.nodes[*v as usize].placement);
+        let pos = if self.fifo {
+            self.queue.iter().position(allowed)?
+        } else {
+            self.queue.iter().rposition(allowed)?
+        };
+        let u = self.queue.remove(pos) as usize;
+        self.gate.take(self.nodes[u].placement);
         self.visited_count += 1;
 
         let start_offset = self.adj_list_rev_offsets[u] as usize;
@@ -148,13 +330,13 @@ impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> Iterator
         debug_assert!(start_offset <= end_offset);
 
         // Process neighbors of 'u'
-        for &v_u8 in &self.adj_list_rev_flat[start_offset..end_offset] {
-            let v = v_u8 as usize;
+        for &v_dep in &self.adj_list_rev_flat[start_offset..end_offset] {
+            let v = v_dep as usize;
             self.in_degree[v] = self.in_degree[v].saturating_sub(1);
             if self.in_degree[v] == 0 {
-                // A queue can be used as a LIFO queue, but it is still valid for topological ordering (the order changes, but the invariants are preserved).
-                // If you want a FIFO queue, use a ring buffer.
-                if self.queue.push(v_u8).is_err() {
+                // Either end of the queue gives a valid topological order;
+                // only the order among ready nodes changes.
+                if self.queue.push(v as u8).is_err() {
                     unreachable!("TopologicalIter queue overflowed");
                 }
             }
@@ -164,20 +346,280 @@ impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> Iterator
     }
 }
 
+/// An iterator over every topological sort of the command graph.
+///
+/// Orders are produced by backtracking, lowest node index first, and each item
+/// is the full sequence of node indices. The number of orders grows
+/// factorially with the number of independent nodes, so callers should bound
+/// the iteration (e.g. with `take`).
+pub struct AllTopoIter<'a, const N: usize, const MAX_DEPS_TOTAL: usize> {
+    nodes: &'a [CmdNode<'a>],
+    failed_nodes: util::BitFlags,
+    in_degree: [u8; N],
+    used: util::BitFlags,
+    gate: PlacementGate,
+    order: heapless::Vec<u8, N>,
+    cursor: [u8; N],
+    total_non_failed: usize,
+    started: bool,
+    done: bool,
+    yielded_any: bool,
+}
+
+impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> AllTopoIter<'a, N, MAX_DEPS_TOTAL> {
+    const _ASSERT_N_LE_128: () = assert!(
+        N <= 128,
+        "AllTopoIter uses a 128-bit BitFlags, so N cannot exceed 128"
+    );
+
+    pub fn new(
+        explorer: &'a Explorer<N, MAX_DEPS_TOTAL>,
+        failed_nodes: &util::BitFlags,
+    ) -> Result<Self, ExplorerError> {
+        Self::from_nodes(explorer.nodes, failed_nodes, &util::BitFlags::new())
+    }
+
+    /// Same skipping rules as [`TopologicalIter::from_nodes`].
+    pub(crate) fn from_nodes(
+        nodes: &'a [CmdNode],
+        failed_nodes: &util::BitFlags,
+        disabled_nodes: &util::BitFlags,
+    ) -> Result<Self, ExplorerError> {
+        let len = nodes.len();
+        if len > N {
+            return Err(ExplorerError::TooManyCommands);
+        }
+
+        let mut skipped = *failed_nodes;
+        skipped |= *disabled_nodes;
+
+        let mut in_degree: [u8; N] = [0; N];
+        let mut total_non_failed = 0;
+        for (i, node) in nodes.iter().enumerate() {
+            if skipped.get(i).unwrap_or(false) {
+                continue;
+            }
+            total_non_failed += 1;
+            for &dep_idx in node.deps.iter() {
+                if dep_idx as usize >= len {
+                    return Err(ExplorerError::InvalidDependencyIndex);
+                }
+                if disabled_nodes.get(dep_idx as usize).unwrap_or(false) {
+                    continue;
+                }
+                in_degree[i] = in_degree[i].saturating_add(1);
+            }
+        }
+
+        Ok(Self {
+            nodes,
+            failed_nodes: skipped,
+            in_degree,
+            used: util::BitFlags::new(),
+            gate: PlacementGate::new(nodes, |i| skipped.get(i).unwrap_or(false)),
+            order: heapless::Vec::new(),
+            cursor: [0; N],
+            total_non_failed,
+            started: false,
+            done: false,
+            yielded_any: false,
+        })
+    }
+
+    /// Checks if the graph has no valid order at all, once iteration is exhausted.
+    pub fn is_cycle_detected(&self) -> bool {
+        self.done && !self.yielded_any && self.total_non_failed > 0
+    }
+
+    /// Adds `u` to the current order and releases the nodes that depend on it.
+    fn select(&mut self, u: usize) {
+        self.used.set(u).ok();
+        self.gate.take(self.nodes[u].placement);
+        // `order` never exceeds the node count, which is bounded by N.
+        self.order.push(u as u8).ok();
+        for (v, node) in self.nodes.iter().enumerate() {
+            for &dep in node.deps.iter() {
+                if dep as usize == u {
+                    self.in_degree[v] = self.in_degree[v].saturating_sub(1);
+                }
+            }
+        }
+    }
+
+    /// Removes the last node from the current order, undoing [`Self::select`].
+    fn unselect(&mut self) {
+        let Some(u) = self.order.pop() else {
+            return;
+        };
+        let u = u as usize;
+        self.used.clear(u).ok();
+        self.gate.give_back(self.nodes[u].placement);
+        for (v, node) in self.nodes.iter().enumerate() {
+            for &dep in node.deps.iter() {
+                if dep as usize == u {
+                    self.in_degree[v] = self.in_degree[v].saturating_add(1);
+                }
+            }
+        }
+    }
+}
+
+impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> Iterator
+    for AllTopoIter<'a, N, MAX_DEPS_TOTAL>
+{
+    type Item = heapless::Vec<u8, N>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.started {
+            // Step back from the order yielded last time and search onwards.
+            if self.order.is_empty() {
+                self.done = true;
+                return None;
+            }
+            self.unselect();
+        }
+        self.started = true;
+
+        loop {
+            let depth = self.order.len();
+            if depth == self.total_non_failed {
+                self.yielded_any = true;
+                return Some(self.order.clone());
+            }
+
+            let candidate = (self.cursor[depth] as usize..self.nodes.len()).find(|&i| {
+                self.in_degree[i] == 0
+                    && !self.used.get(i).unwrap_or(true)
+                    && !self.failed_nodes.get(i).unwrap_or(true)
+                    && self.gate.allows(self.nodes[i].placement)
+            });
+
+            match candidate {
+                Some(u) => {
+                    self.cursor[depth] = (u + 1) as u8;
+                    if depth + 1 < N {
+                        self.cursor[depth + 1] = 0;
+                    }
+                    self.select(u);
+                }
+                None => {
+                    if depth == 0 {
+                        self.done = true;
+                        return None;
+                    }
+                    self.unselect();
+                }
+            }
+        }
+    }
+}
+
+/// A source of candidate command orders for [`Explorer::explore_with_strategy`].
+///
+/// [`AllTopoIter`] (exhaustive backtracking) is the default. Custom strategies
+/// can try likely orders first or do seeded random restarts when exhaustive
+/// search is infeasible. Orders that violate a dependency are skipped by the
+/// explorer, so a strategy does not have to be exact.
+pub trait PermutationStrategy<const N: usize> {
+    /// The next order to try, as node indices, or `None` when exhausted.
+    fn next_order(&mut self) -> Option<heapless::Vec<u8, N>>;
+
+    /// Whether the strategy found that no valid order exists.
+    fn is_cycle_detected(&self) -> bool {
+        false
+    }
+}
+
+impl<const N: usize, const MAX_DEPS_TOTAL: usize> PermutationStrategy<N>
+    for AllTopoIter<'_, N, MAX_DEPS_TOTAL>
+{
+    fn next_order(&mut self) -> Option<heapless::Vec<u8, N>> {
+        self.next()
+    }
+
+    fn is_cycle_detected(&self) -> bool {
+        AllTopoIter::is_cycle_detected(self)
+    }
+}
+
+/// Default number of attempts per write: the write and one retry.
+pub(crate) const DEFAULT_MAX_ATTEMPTS: u8 = 2;
+
+/// Default pause after a failed and after a successful write, in units of the
+/// executor's delay ([`SpinDelay`] iterations by default).
+pub(crate) const DEFAULT_WRITE_DELAY: u32 = 1_000;
+
+/// Logs a write the way [`PrefixExecutor`] does, e.g. `I2C WRITE @3C:\n00 AE \n`.
+pub(crate) fn log_write<W: core::fmt::Write>(writer: &mut W, addr: u8, bytes: &[u8]) {
+    dvcdbg_log!(writer, "I2C WRITE @{addr:02X}:\n");
+    for b in bytes.iter() {
+        dvcdbg_log!(writer, "{b:02X} ");
+    }
+    dvcdbg_log!(writer, "\n");
+}
+
 /// A command executor that prepends a prefix to each command.
-pub struct PrefixExecutor<const INIT_SEQUENCE_LEN: usize, const CMD_BUFFER_SIZE: usize> {
+///
+/// The executor tracks which addresses it has initialized, so it is tied to a
+/// single bus. See [`PrefixExecutor::reset_init_state`] for reuse.
+///
+/// `CMD_BUFFER_SIZE` must fit the prefix plus one command byte, and the init
+/// sequence with a prefix in front of every byte (`INIT_SEQUENCE_LEN * 2`).
+/// Both are checked when `new` is instantiated, so mis-sizing is a compile error.
+///
+/// Before the first command to an address, the executor probes the device
+/// and sends the init sequence only if it responds. With an empty init
+/// sequence this step is skipped entirely: there is no presence check, and
+/// an absent device shows up as a failed command instead.
+///
+/// Probe results are cached per address, so an address confirmed absent is
+/// not probed again on later passes; [`PrefixExecutor::reset_init_state`]
+/// clears the cache along with the init state.
+///
+/// A failed write is attempted twice in total by default, with a short spin
+/// between attempts. [`PrefixExecutor::with_max_attempts`] and
+/// [`PrefixExecutor::with_delay`] adapt this to a device's recovery time; `D`
+/// is the delay used for every pause.
+pub struct PrefixExecutor<
+    const INIT_SEQUENCE_LEN: usize,
+    const CMD_BUFFER_SIZE: usize,
+    D = SpinDelay,
+> {
     buffer: [u8; CMD_BUFFER_SIZE],
     buffer_len: usize,
     initialized_addrs: util::BitFlags,
+    /// Addresses whose presence has been checked.
+    probed_addrs: util::BitFlags,
+    /// Of `probed_addrs`, the ones that acknowledged.
+    present_addrs: util::BitFlags,
     prefix: u8,
     init_sequence: [u8; INIT_SEQUENCE_LEN],
     init_sequence_len: usize,
+    chunked: bool,
+    max_attempts: u8,
+    retry_delay: u32,
+    post_write_delay: u32,
+    delay: D,
 }
 
 impl<const INIT_SEQUENCE_LEN: usize, const CMD_BUFFER_SIZE: usize>
     PrefixExecutor<INIT_SEQUENCE_LEN, CMD_BUFFER_SIZE>
 {
     pub fn new(prefix: u8, init_sequence: &[u8]) -> Self {
+        const {
+            assert!(
+                CMD_BUFFER_SIZE > Self::PREFIX_LEN,
+                "CMD_BUFFER_SIZE must hold the prefix and at least one command byte"
+            );
+            assert!(
+                INIT_SEQUENCE_LEN * Self::INIT_STRIDE <= CMD_BUFFER_SIZE,
+                "CMD_BUFFER_SIZE must hold the prefixed init sequence (INIT_SEQUENCE_LEN * 2 bytes)"
+            );
+        }
+
         let mut init_seq_arr = [0u8; INIT_SEQUENCE_LEN];
         let init_seq_len = init_sequence.len().min(INIT_SEQUENCE_LEN);
         if init_seq_len > 0 {
@@ -188,49 +630,183 @@ impl<const INIT_SEQUENCE_LEN: usize, const CMD_BUFFER_SIZE: usize>
             buffer: [0; CMD_BUFFER_SIZE],
             buffer_len: 0,
             initialized_addrs: util::BitFlags::new(),
+            probed_addrs: util::BitFlags::new(),
+            present_addrs: util::BitFlags::new(),
             prefix,
             init_sequence: init_seq_arr,
             init_sequence_len: init_seq_len,
+            chunked: false,
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            retry_delay: DEFAULT_WRITE_DELAY,
+            post_write_delay: DEFAULT_WRITE_DELAY,
+            delay: SpinDelay,
         }
     }
+}
+
+impl<const INIT_SEQUENCE_LEN: usize, const CMD_BUFFER_SIZE: usize, D>
+    PrefixExecutor<INIT_SEQUENCE_LEN, CMD_BUFFER_SIZE, D>
+{
+    /// Number of prefix bytes sent in front of every command byte.
+    const PREFIX_LEN: usize = 1;
+
+    /// Bytes one init command occupies in the buffer: prefix plus the command byte.
+    const INIT_STRIDE: usize = Self::PREFIX_LEN + 1;
+
+    /// Set the recovery pause after a failed write, in microseconds of the
+    /// delay (spin-loop iterations with the default [`SpinDelay`]).
+    pub fn with_retry_delay(mut self, us: u32) -> Self {
+        self.retry_delay = us;
+        self
+    }
 
-    fn short_delay() {
-        for _ in 0..1_000 {
-            core::hint::spin_loop();
+    /// Set the pause after every successful write (including the init
+    /// sequence), in microseconds of the delay (spin-loop iterations with the
+    /// default [`SpinDelay`]).
+    pub fn with_post_write_delay(mut self, us: u32) -> Self {
+        self.post_write_delay = us;
+        self
+    }
+
+    /// Set how many times a write is attempted before the command fails
+    /// (default 2). `0` counts as 1.
+    pub fn with_max_attempts(mut self, attempts: u8) -> Self {
+        self.max_attempts = attempts.max(1);
+        self
+    }
+
+    /// Replace the busy-wait [`SpinDelay`] with another delay, e.g. a HAL
+    /// timer, making the pause lengths real microseconds.
+    ///
+    /// ```ignore
+    /// let executor = PrefixExecutor::<0, BUF>::new(PREFIX, &[])
+    ///     .with_delay(timer_delay)
+    ///     .with_retry_delay(5_000);
+    /// ```
+    pub fn with_delay<D2: DelayCompat>(
+        self,
+        delay: D2,
+    ) -> PrefixExecutor<INIT_SEQUENCE_LEN, CMD_BUFFER_SIZE, D2> {
+        PrefixExecutor {
+            buffer: self.buffer,
+            buffer_len: self.buffer_len,
+            initialized_addrs: self.initialized_addrs,
+            probed_addrs: self.probed_addrs,
+            present_addrs: self.present_addrs,
+            prefix: self.prefix,
+            init_sequence: self.init_sequence,
+            init_sequence_len: self.init_sequence_len,
+            chunked: self.chunked,
+            max_attempts: self.max_attempts,
+            retry_delay: self.retry_delay,
+            post_write_delay: self.post_write_delay,
+            delay,
         }
     }
 
-    fn write_with_retry<I2C, W>(
+    /// Split commands longer than the buffer into several prefixed writes.
+    ///
+    /// Only enable this for devices that accept a long command spread over
+    /// consecutive transactions. When disabled (the default), an oversized
+    /// command fails with [`ExecutorError::CommandTooLarge`].
+    pub fn with_chunking(mut self, enabled: bool) -> Self {
+        self.chunked = enabled;
+        self
+    }
+
+    /// Forget which addresses have already received the init sequence, and
+    /// every cached probe result.
+    ///
+    /// Call this before reusing the executor on a different bus, otherwise
+    /// devices that share an address with one on the previous bus would be
+    /// treated as already initialized. Call it as well when a device may have
+    /// been powered up since it was found absent.
+    pub fn reset_init_state(&mut self) {
+        self.initialized_addrs.clear_all();
+        self.probed_addrs.clear_all();
+        self.present_addrs.clear_all();
+    }
+
+    /// Whether `addr` acknowledges a probe, asking the bus only the first time.
+    fn is_present<I2C>(&mut self, i2c: &mut I2C, addr: u8) -> Result<bool, ExecutorError>
+    where
+        I2C: crate::compat::I2cCompat,
+    {
+        let idx = addr as usize;
+        if self
+            .probed_addrs
+            .get(idx)
+            .map_err(ExecutorError::BitFlags)?
+        {
+            return self.present_addrs.get(idx).map_err(ExecutorError::BitFlags);
+        }
+        let present = matches!(i2c.probe(addr), Ok(true));
+        self.probed_addrs
+            .set(idx)
+            .map_err(ExecutorError::BitFlags)?;
+        if present {
+            self.present_addrs
+                .set(idx)
+                .map_err(ExecutorError::BitFlags)?;
+        }
+        Ok(present)
+    }
+
+    /// Writes `prefix + bytes` from the internal buffer. The caller guarantees it fits.
+    fn write_prefixed<I2C, W>(
+        &mut self,
         i2c: &mut I2C,
         addr: u8,
         bytes: &[u8],
         writer: &mut W,
+    ) -> Result<(), ExecutorError>
+    where
+        I2C: crate::compat::I2cCompat,
+        <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+        W: core::fmt::Write,
+        D: ExecutorDelay,
+    {
+        self.buffer_len = 0;
+        self.buffer[self.buffer_len] = self.prefix;
+        self.buffer_len += 1;
+
+        let end = self.buffer_len + bytes.len();
+        self.buffer[self.buffer_len..end].copy_from_slice(bytes);
+        self.buffer_len = end;
+
+        self.write_with_retry(i2c, addr, self.buffer_len, writer)
+            .map_err(ExecutorError::I2cError)
+    }
+
+    /// Writes the first `len` bytes of the buffer, up to `max_attempts` times
+    /// with `retry_delay` after each failure, then pauses `post_write_delay`.
+    fn write_with_retry<I2C, W>(
+        &mut self,
+        i2c: &mut I2C,
+        addr: u8,
+        len: usize,
+        writer: &mut W,
     ) -> Result<(), crate::error::ErrorKind>
     where
         I2C: crate::compat::I2cCompat,
         <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
         W: core::fmt::Write,
+        D: ExecutorDelay,
     {
+        let bytes = &self.buffer[..len];
         let mut last_error = None;
-        for _attempt in 0..2 {
-            writeln!(writer, "I2C WRITE @{addr:02X}:").ok();
-            for b in bytes.iter() {
-                write!(writer, "{b:02X} ").ok();
-            }
-            writeln!(writer).ok();
+        for _attempt in 0..self.max_attempts {
+            log_write(writer, addr, bytes);
             match i2c.write(addr, bytes) {
                 Ok(_) => {
-                    Self::short_delay();
+                    self.delay.pause(self.post_write_delay);
                     return Ok(());
                 }
                 Err(e) => {
-                    let compat_err = e.to_compat(Some(addr));
-                    last_error = Some(compat_err);
-                    let _ = util::write_formatted_ascii_safe(
-                        writer,
-                        format_args!("[I2C retry error] {compat_err}"),
-                    );
-                    Self::short_delay();
+                    let compat_err = e.to_compat_at(addr);
+                    last_error = Some(compat_err.kind);
+                    dvcdbg_log!(@ascii writer, "[I2C retry error] {compat_err}");
+                    self.delay.pause(self.retry_delay);
                 }
             }
         }
@@ -254,21 +830,22 @@ where
 {
     match executor.exec(i2c, addr, cmd_bytes, writer) {
         Ok(_) => {
-            write!(writer, "[E] OK {cmd_idx}\r\n").ok();
+            dvcdbg_log!(writer, "[E] OK {cmd_idx}\r\n");
             Ok(())
         }
         Err(e) => {
-            write!(writer, "[E] FAIL {cmd_idx}: {e}\r\n").ok();
-            Err(e.into())
+            dvcdbg_log!(writer, "[E] FAIL {cmd_idx}: {e}\r\n");
+            Err(ExplorerError::from(e).with_command_index(cmd_idx))
         }
     }
 }
 
-impl<I2C, const INIT_SEQ_SIZE: usize, const CMD_BUFFER_SIZE: usize>
-    CmdExecutor<I2C, CMD_BUFFER_SIZE> for PrefixExecutor<INIT_SEQ_SIZE, CMD_BUFFER_SIZE>
+impl<I2C, D, const INIT_SEQ_SIZE: usize, const CMD_BUFFER_SIZE: usize>
+    CmdExecutor<I2C, CMD_BUFFER_SIZE> for PrefixExecutor<INIT_SEQ_SIZE, CMD_BUFFER_SIZE, D>
 where
     I2C: crate::compat::I2cCompat,
     <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    D: ExecutorDelay,
 {
     fn exec<W>(
         &mut self,
@@ -288,111 +865,369 @@ where
             .map_err(ExecutorError::BitFlags)?
             && self.init_sequence_len > 0
         {
-            if (self.init_sequence_len * 2) > CMD_BUFFER_SIZE {
-                return Err(ExecutorError::BufferOverflow);
-            }
-
-            core::fmt::Write::write_str(writer, "[Info] I2C initializing for ").ok();
-            crate::compat::util::write_bytes_hex_fmt(writer, &[addr])
-                .map_err(|_| ExecutorError::ExecFailed)?;
-            core::fmt::Write::write_str(writer, "...\r\n").ok();
-            let ack_ok = i2c.probe(addr).is_ok();
+            dvcdbg_log!(writer, "[Info] I2C initializing for {addr:02X}...\r\n");
+            let ack_ok = self.is_present(i2c, addr)?;
 
             if ack_ok {
-                core::fmt::Write::write_str(writer, "[Info] Device found at ").ok();
-                crate::compat::util::write_bytes_hex_fmt(writer, &[addr]).ok();
-                core::fmt::Write::write_str(writer, ", sending init sequence...\r\n").ok();
+                dvcdbg_log!(
+                    writer,
+                    "[Info] Device found at {addr:02X}, sending init sequence...\r\n"
+                );
                 for (i, &c) in self.init_sequence[..self.init_sequence_len]
                     .iter()
                     .enumerate()
                 {
-                    self.buffer[2 * i] = self.prefix;
-                    self.buffer[2 * i + 1] = c;
+                    // Fits: `new` checks INIT_SEQUENCE_LEN * INIT_STRIDE <= CMD_BUFFER_SIZE.
+                    self.buffer[Self::INIT_STRIDE * i] = self.prefix;
+                    self.buffer[Self::INIT_STRIDE * i + Self::PREFIX_LEN] = c;
                 }
 
-                Self::write_with_retry(
+                self.write_with_retry(
                     i2c,
                     addr,
-                    &self.buffer[..self.init_sequence_len * 2],
+                    self.init_sequence_len * Self::INIT_STRIDE,
                     writer,
                 )
                 .map_err(ExecutorError::I2cError)?;
 
-                Self::short_delay();
-
                 self.initialized_addrs
                     .set(addr_idx)
                     .map_err(ExecutorError::BitFlags)?;
 
-                core::fmt::Write::write_str(writer, "[Info] I2C initialized for ").ok();
-                crate::compat::util::write_bytes_hex_fmt(writer, &[addr]).ok();
-                core::fmt::Write::write_str(writer, "\r\n").ok();
+                dvcdbg_log!(writer, "[Info] I2C initialized for {addr:02X}\r\n");
             }
         }
 
-        self.buffer_len = 0;
-        self.buffer[self.buffer_len] = self.prefix;
-        self.buffer_len += 1;
+        let required = Self::PREFIX_LEN + cmd.len();
+        if required <= CMD_BUFFER_SIZE {
+            return self.write_prefixed(i2c, addr, cmd, writer);
+        }
+        if !self.chunked {
+            return Err(ExecutorError::CommandTooLarge {
+                required,
+                available: CMD_BUFFER_SIZE,
+            });
+        }
 
-        if self.buffer_len + cmd.len() > CMD_BUFFER_SIZE {
-            return Err(ExecutorError::BufferOverflow);
+        for chunk in cmd.chunks(CMD_BUFFER_SIZE - Self::PREFIX_LEN) {
+            self.write_prefixed(i2c, addr, chunk, writer)?;
         }
-        let end = self.buffer_len + cmd.len();
-        self.buffer[self.buffer_len..end].copy_from_slice(cmd);
-        self.buffer_len = end;
+        Ok(())
+    }
 
-        Self::write_with_retry(i2c, addr, &self.buffer[..self.buffer_len], writer)
-            .map_err(ExecutorError::I2cError)
+    /// Microseconds once a HAL delay is plugged in with
+    /// [`PrefixExecutor::with_delay`]; with the default [`SpinDelay`] this
+    /// only spins `us` iterations.
+    fn pause_us(&mut self, us: u32) {
+        self.delay.pause(us);
+    }
+}
+
+/// A command executor that reads a status register back after every command.
+///
+/// Each command goes through the inner executor; once it is written,
+/// `status_reg` is read with a `write_read` and `status & mask` must equal
+/// `expected & mask`, otherwise the command fails with
+/// [`ExecutorError::ExecFailed`] and the expected and actual bytes are logged.
+/// A failed status read surfaces as [`ExecutorError::I2cError`].
+///
+/// ```ignore
+/// // Bit 6 of register 0x00 reports "display on".
+/// let mut executor = VerifyingExecutor::new(PrefixExecutor::<0, BUF>::new(0x00, &[]), 0x00, 0x40, 0x40);
+/// ```
+pub struct VerifyingExecutor<E> {
+    inner: E,
+    status_reg: u8,
+    mask: u8,
+    expected: u8,
+}
+
+impl<E> VerifyingExecutor<E> {
+    pub fn new(inner: E, status_reg: u8, mask: u8, expected: u8) -> Self {
+        Self {
+            inner,
+            status_reg,
+            mask,
+            expected,
+        }
+    }
+
+    /// Consume the verifier, returning the wrapped executor.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+impl<I2C, E, const CMD_BUFFER_SIZE: usize> CmdExecutor<I2C, CMD_BUFFER_SIZE>
+    for VerifyingExecutor<E>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+{
+    fn exec<W: core::fmt::Write>(
+        &mut self,
+        i2c: &mut I2C,
+        addr: u8,
+        cmd: &[u8],
+        writer: &mut W,
+    ) -> Result<(), ExecutorError> {
+        self.inner.exec(i2c, addr, cmd, writer)?;
+
+        let mut status = [0u8; 1];
+        i2c.write_read(addr, &[self.status_reg], &mut status)
+            .map_err(|e| ExecutorError::I2cError(e.to_compat(Some(addr))))?;
+
+        if status[0] & self.mask != self.expected & self.mask {
+            dvcdbg_log!(
+                writer,
+                "[verify] {addr:02X} reg {:02X}: expected {:02X} (mask {:02X}), got {:02X}\r\n",
+                self.status_reg,
+                self.expected,
+                self.mask,
+                status[0]
+            );
+            return Err(ExecutorError::ExecFailed);
+        }
+        Ok(())
+    }
+
+    fn pause_us(&mut self, us: u32) {
+        self.inner.pause_us(us);
+    }
+}
+
+/// Per-command buffer size for `nodes`: the longest command plus the prefix byte.
+pub const fn cmd_buffer_size(nodes: &[CmdNode]) -> usize {
+    let mut max_len = 0;
+    let mut i = 0;
+    while i < nodes.len() {
+        let len = nodes[i].bytes.len();
+        if len > max_len {
+            max_len = len;
+        }
+        i += 1;
+    }
+    max_len + 1
+}
+
+/// Buffer size for a batch of every command in `nodes` behind one prefix
+/// byte, as written by `pruning_explorer`.
+pub const fn batch_buffer_size(nodes: &[CmdNode]) -> usize {
+    let mut total = 0;
+    let mut i = 0;
+    while i < nodes.len() {
+        total += nodes[i].bytes.len();
+        i += 1;
     }
+    total + 1
 }
 
+/// Total number of dependency edges in `nodes` (the `MAX_DEPS` parameter).
+pub const fn total_deps(nodes: &[CmdNode]) -> usize {
+    let mut total = 0;
+    let mut i = 0;
+    while i < nodes.len() {
+        total += nodes[i].deps.len();
+        i += 1;
+    }
+    total
+}
+
+/// Narrows a `usize` dependency list to the [`DepIndex`] indices
+/// `CmdNode::deps` uses.
+///
+/// Panics if `deps` does not hold exactly `N` entries or an index exceeds
+/// `DepIndex::MAX`; in a `const` context that is a compile error rather than
+/// a silent truncation. Usually called through [`deps!`](crate::deps).
+pub const fn deps_to_index<const N: usize>(deps: &[usize]) -> [DepIndex; N] {
+    assert!(deps.len() == N, "dependency list length mismatch");
+    let mut out = [0; N];
+    let mut i = 0;
+    while i < N {
+        assert!(
+            deps[i] <= DepIndex::MAX as usize,
+            "dependency index does not fit in DepIndex"
+        );
+        out[i] = deps[i] as DepIndex;
+        i += 1;
+    }
+    out
+}
+
+/// Former name of [`deps_to_index`], from when `DepIndex` was a `u8`.
+#[deprecated(note = "renamed to `deps_to_index`; the result is `[DepIndex; N]`, not `[u8; N]`")]
+pub const fn deps_to_u8<const N: usize>(deps: &[usize]) -> [DepIndex; N] {
+    deps_to_index(deps)
+}
+
+/// Declares a command graph.
+///
+/// The expression form returns `(&Explorer, PrefixExecutor)`. The `mod` form
+/// declares a module holding the graph and every size derived from it, so
+/// the runner macros never need hand-written buffer sizes:
+///
+/// ```rust,ignore
+/// nodes!(mod display, prefix = 0x00, [[0xAE], [0xAF] @ [0]]);
+/// // display::{NODES, EXPLORER, PREFIX, N, MAX_DEPS, CMD_BUFFER_SIZE, BATCH_BUFFER_SIZE, executor(delay)}
+/// pruning_sort!(graph = display, &mut i2c, &mut serial)?;
+/// ```
+///
+/// An entry may end with `=> First` or `=> Last` to set its [`Placement`],
+/// e.g. `[0xAF] @ [1] => Last`, and with `delay = <us>` (before the
+/// placement) to pause after the command, e.g. `[0x01] delay = 1000`.
 #[macro_export]
 macro_rules! nodes {
+    (
+        mod $name:ident,
+        prefix = $prefix:expr,
+        [ $( [ $( $b:expr ),* ] $( @ [ $( $d:expr ),* ] )? $( delay = $us:expr )? $( => $place:ident )? ),* $(,)? ]
+    ) => {
+        pub mod $name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            pub static NODES: &[$crate::explore::explorer::CmdNode] = &[
+                $(
+                    $crate::explore::explorer::CmdNode {
+                        bytes: &[ $( $b ),* ],
+                        deps: &[ $( $( $d ),* )? ],
+                        placement: $crate::__node_placement!($( $place )?),
+                        delay_us: $crate::__node_delay!($( $us )?),
+                    }
+                ),*
+            ];
+            pub const PREFIX: u8 = $prefix;
+            pub const N: usize = NODES.len();
+            pub const MAX_DEPS: usize = $crate::explore::explorer::total_deps(NODES);
+            pub const CMD_BUFFER_SIZE: usize = $crate::explore::explorer::cmd_buffer_size(NODES);
+            pub const BATCH_BUFFER_SIZE: usize =
+                $crate::explore::explorer::batch_buffer_size(NODES);
+
+            pub static EXPLORER: $crate::explore::explorer::Explorer<N, MAX_DEPS> =
+                $crate::explore::explorer::Explorer::new(NODES);
+
+            /// An executor for the graph pausing with `delay`, so `delay = <us>`
+            /// entries are real microseconds.
+            pub fn executor<D: $crate::compat::DelayCompat>(
+                delay: D,
+            ) -> $crate::explore::explorer::PrefixExecutor<0, CMD_BUFFER_SIZE, D> {
+                $crate::explore::explorer::PrefixExecutor::new(PREFIX, &[]).with_delay(delay)
+            }
+        }
+    };
     (
         prefix = $prefix:expr,
-        [ $( [ $( $b:expr ),* ] $( @ [ $( $d:expr ),* ] )? ),* $(,)? ]
+        [ $( [ $( $b:expr ),* ] $( @ [ $( $d:expr ),* ] )? $( delay = $us:expr )? $( => $place:ident )? ),* $(,)? ]
     ) => {{
         static NODES: &[$crate::explore::explorer::CmdNode] = &[
             $(
                 $crate::explore::explorer::CmdNode {
                     bytes: &[ $( $b ),* ],
                     deps: &[ $( $( $d ),* )? ],
+                    placement: $crate::__node_placement!($( $place )?),
+                    delay_us: $crate::__node_delay!($( $us )?),
                 }
             ),*
         ];
 
-        const MAX_CMD_LEN_INTERNAL: usize = {
-            let mut max_len = 0;
-            let mut i = 0;
-            while i < NODES.len() {
-                let len = NODES[i].bytes.len();
-                if len > max_len {
-                    max_len = len;
-                }
-                i += 1;
-            }
-            max_len
-        };
-        const MAX_DEPS_TOTAL_INTERNAL: usize = {
-            let mut total_deps = 0;
-            let mut i = 0;
-            while i < NODES.len() {
-                total_deps += NODES[i].deps.len();
-                i += 1;
-            }
-            total_deps
-        };
+        const MAX_DEPS_TOTAL_INTERNAL: usize = $crate::explore::explorer::total_deps(NODES);
 
         static EXPLORER: $crate::explore::explorer::Explorer<{NODES.len()}, {MAX_DEPS_TOTAL_INTERNAL}> =
             $crate::explore::explorer::Explorer::new(NODES);
 
         (
             &EXPLORER,
-            $crate::explore::explorer::PrefixExecutor::<0, { MAX_CMD_LEN_INTERNAL + 1 }>::new($prefix, &[])
+            $crate::explore::explorer::PrefixExecutor::<
+                0,
+                { $crate::explore::explorer::cmd_buffer_size(NODES) },
+            >::new($prefix, &[])
         )
     }};
 }
 
+/// Builds a `[CmdNode; N]` array from single-byte command constants, e.g.
+/// those exported by a driver crate, so its documented init order can be
+/// explored directly.
+///
+/// Each entry is a constant path with optional deps and placement, in the
+/// same syntax as [`nodes!`](crate::nodes):
+///
+/// ```rust,ignore
+/// use ssd1306::command::*;
+/// static NODES: [CmdNode; 3] = nodes_from_consts!([
+///     DISPLAY_OFF,
+///     SET_MULTIPLEX_RATIO @ [0],
+///     DISPLAY_ON @ [1] => Last,
+/// ]);
+/// static EXPLORER: Explorer<3, 2> = Explorer::new(&NODES);
+/// ```
+#[macro_export]
+macro_rules! nodes_from_consts {
+    ([ $( $( $c:ident )::+ $( @ [ $( $d:expr ),* ] )? $( => $place:ident )? ),* $(,)? ]) => {
+        [
+            $(
+                $crate::explore::explorer::CmdNode {
+                    bytes: &[ $( $c )::+ ],
+                    deps: &[ $( $( $d ),* )? ],
+                    placement: $crate::__node_placement!($( $place )?),
+                    delay_us: 0,
+                }
+            ),*
+        ]
+    };
+}
+
+/// Converts a `&[usize]` dependency list into a `&'static [DepIndex]` at compile time.
+///
+/// ```rust,ignore
+/// const OLD_DEPS: &[usize] = &[0, 3];
+/// static NODE: CmdNode = CmdNode::new(&[0xAF], deps!(OLD_DEPS));
+/// ```
+#[macro_export]
+macro_rules! deps {
+    ($deps:expr) => {{
+        const DEPS: &[usize] = $deps;
+        const OUT: [$crate::explore::explorer::DepIndex; DEPS.len()] =
+            $crate::explore::explorer::deps_to_index::<{ DEPS.len() }>(DEPS);
+        &OUT
+    }};
+}
+
+/// Former name of [`deps!`](crate::deps), from when
+/// [`DepIndex`](crate::explore::explorer::DepIndex) was a `u8`.
+#[deprecated(note = "renamed to `deps!`; the result is `&[DepIndex]`, not `&[u8]`")]
+#[macro_export]
+macro_rules! deps_u8 {
+    ($deps:expr) => {
+        $crate::deps!($deps)
+    };
+}
+
+/// Maps the optional `=> First` / `=> Last` suffix of a `nodes!` entry to a [`Placement`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __node_placement {
+    () => {
+        $crate::explore::explorer::Placement::Any
+    };
+    ($place:ident) => {
+        $crate::explore::explorer::Placement::$place
+    };
+}
+
+/// Maps the optional `delay = <us>` suffix of a `nodes!` entry to [`CmdNode::delay_us`].
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __node_delay {
+    () => {
+        0
+    };
+    ($us:expr) => {
+        $us
+    };
+}
+
 /// simple macro to count comma-separated expressions at compile time
 #[macro_export]
 macro_rules! count_exprs {
@@ -400,14 +1235,225 @@ macro_rules! count_exprs {
     ($x:expr $(, $xs:expr)*) => (1usize + $crate::count_exprs!($($xs),*));
 }
 
+/// Number of placed-node sets [`Explorer::count_topological_sorts`] remembers.
+const COUNT_MEMO_CAPACITY: usize = 256;
+
+/// The dependency graph as bit masks, for [`Explorer::count_topological_sorts`].
+struct OrderGraph<const N: usize> {
+    deps: [u128; N],
+    placements: [Placement; N],
+    /// Unskipped `First` and `Any` nodes, for the placement rules.
+    first: u128,
+    any: u128,
+    /// Every unskipped node: the set a complete order has placed.
+    target: u128,
+    len: usize,
+}
+
+impl<const N: usize> OrderGraph<N> {
+    /// Orders completing the partial order that placed `used`; `None` once
+    /// the memo cannot hold every reachable set.
+    fn count_from<const MEMO: usize>(
+        &self,
+        used: u128,
+        memo: &mut heapless::index_map::FnvIndexMap<u128, usize, MEMO>,
+    ) -> Option<usize> {
+        if used == self.target {
+            return Some(1);
+        }
+        if let Some(&count) = memo.get(&used) {
+            return Some(count);
+        }
+
+        let mut count = 0usize;
+        for i in 0..self.len {
+            let bit = 1u128 << i;
+            let placeable = match self.placements[i] {
+                Placement::First => true,
+                Placement::Any => self.first & !used == 0,
+                Placement::Last => (self.first | self.any) & !used == 0,
+            };
+            if self.target & !used & bit != 0 && self.deps[i] & !used == 0 && placeable {
+                count = count.saturating_add(self.count_from(used | bit, memo)?);
+            }
+        }
+        // Without the entry the search would degrade into enumeration.
+        memo.insert(used, count).ok()?;
+        Some(count)
+    }
+}
+
+/// The command graph to explore.
+///
+/// An `Explorer` only borrows `&'static` nodes and holds no per-bus state, so
+/// a single instance can be shared between any number of buses.
 pub struct Explorer<const N: usize, const MAX_DEPS_TOTAL: usize> {
-    pub(crate) nodes: &'static [CmdNode],
+    pub(crate) nodes: &'static [CmdNode<'static>],
+}
+
+/// Limits applied by [`Explorer::explore_with_strategy`].
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExploreOptions<'a> {
+    max_writes: Option<usize>,
+    abort: Option<&'a AtomicBool>,
+    progress_every: Option<usize>,
+    max_permutations: Option<usize>,
+}
+
+impl<'a> ExploreOptions<'a> {
+    /// No limits.
+    pub const fn new() -> Self {
+        Self {
+            max_writes: None,
+            abort: None,
+            progress_every: None,
+            max_permutations: None,
+        }
+    }
+
+    /// Abort with [`ExplorerError::WriteBudgetExhausted`] once `max_writes`
+    /// commands have been sent, as a safety valve for fragile prototypes.
+    /// Each command counts once; retries inside the executor are not counted.
+    pub const fn with_max_writes(mut self, max_writes: usize) -> Self {
+        self.max_writes = Some(max_writes);
+        self
+    }
+
+    /// Stop cleanly once `flag` is set, e.g. from an ISR. The flag is polled
+    /// before each order and each command; the partial result is returned
+    /// with [`ExploreResult::aborted`] set.
+    pub const fn with_abort(mut self, flag: &'a AtomicBool) -> Self {
+        self.abort = Some(flag);
+        self
+    }
+
+    /// Log a progress line (orders tested, devices solved) every `every`
+    /// orders. `0` disables it.
+    pub const fn with_progress_every(mut self, every: usize) -> Self {
+        self.progress_every = if every == 0 { None } else { Some(every) };
+        self
+    }
+
+    /// Stop after testing `max` orders, e.g. to bound the run time of a weakly
+    /// constrained graph on a slow target. The addresses found so far are
+    /// returned with [`ExploreResult::truncated`] set.
+    pub const fn with_max_permutations(mut self, max: usize) -> Self {
+        self.max_permutations = Some(max);
+        self
+    }
+
+    fn is_aborted(&self) -> bool {
+        self.abort.is_some_and(|flag| flag.load(Ordering::Relaxed))
+    }
+}
+
+/// Order prefixes known to fail on a given address, for
+/// [`Explorer::explore_with_cache`].
+///
+/// Holds up to `CACHE` entries (a power of two), each an address and a 64-bit
+/// FNV hash of the prefix's node indices. A hash collision could skip a
+/// good order, but at 64 bits that is negligible for realistic graphs.
+pub struct FailedPrefixCache<const CACHE: usize> {
+    set: heapless::index_set::FnvIndexSet<(u8, u64), CACHE>,
+}
+
+impl<const CACHE: usize> Default for FailedPrefixCache<CACHE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const CACHE: usize> FailedPrefixCache<CACHE> {
+    pub const fn new() -> Self {
+        Self {
+            set: heapless::index_set::FnvIndexSet::new(),
+        }
+    }
+
+    /// Number of failed prefixes remembered.
+    pub fn len(&self) -> usize {
+        self.set.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty()
+    }
+
+    /// Forget every prefix, e.g. after swapping the device.
+    pub fn clear(&mut self) {
+        self.set.clear();
+    }
+
+    /// Remembers that `prefix` failed on `addr`; ignored once full.
+    fn insert(&mut self, addr: u8, prefix: &[u8]) {
+        let hash = prefix.iter().fold(FNV_OFFSET, |h, &idx| fnv_step(h, idx));
+        self.set.insert((addr, hash)).ok();
+    }
+
+    /// Length of the shortest prefix of `order` known to fail on `addr`.
+    fn known_bad_prefix(&self, addr: u8, order: &[u8]) -> Option<usize> {
+        let mut hash = FNV_OFFSET;
+        for (pos, &idx) in order.iter().enumerate() {
+            hash = fnv_step(hash, idx);
+            if self.set.contains(&(addr, hash)) {
+                return Some(pos + 1);
+            }
+        }
+        None
+    }
 }
 
-pub struct ExploreResult {
-    pub found_addrs: [u8; I2C_ADDRESS_COUNT],
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+
+fn fnv_step(hash: u64, byte: u8) -> u64 {
+    (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+}
+
+/// Outcome of an exploration run.
+///
+/// `MAX_FOUND` bounds how many solved addresses are kept. The default covers
+/// the whole address space; RAM-constrained targets expecting a handful of
+/// devices can shrink it with [`Explorer::explore_with_capacity`].
+pub struct ExploreResult<const MAX_FOUND: usize = I2C_ADDRESS_COUNT> {
+    pub found_addrs: [u8; MAX_FOUND],
     pub found_addrs_len: usize,
     pub permutations_tested: usize,
+    /// `true` if the run was stopped by the abort flag of [`ExploreOptions`].
+    pub aborted: bool,
+    /// `true` if untested orders remained when the permutation cap of
+    /// [`ExploreOptions::with_max_permutations`] was reached.
+    pub truncated: bool,
+}
+
+impl<const MAX_FOUND: usize> ExploreResult<MAX_FOUND> {
+    /// The addresses that were found, without the unused tail of `found_addrs`.
+    pub fn found(&self) -> &[u8] {
+        &self.found_addrs[..self.found_addrs_len.min(MAX_FOUND)]
+    }
+}
+
+/// Compares only the valid `found_addrs[..found_addrs_len]` prefix, so stale
+/// bytes past the end never affect equality.
+impl<const MAX_FOUND: usize> PartialEq for ExploreResult<MAX_FOUND> {
+    fn eq(&self, other: &Self) -> bool {
+        self.found() == other.found()
+            && self.permutations_tested == other.permutations_tested
+            && self.aborted == other.aborted
+            && self.truncated == other.truncated
+    }
+}
+
+impl<const MAX_FOUND: usize> Eq for ExploreResult<MAX_FOUND> {}
+
+impl<const MAX_FOUND: usize> core::fmt::Debug for ExploreResult<MAX_FOUND> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("ExploreResult")
+            .field("found_addrs", &self.found())
+            .field("permutations_tested", &self.permutations_tested)
+            .field("aborted", &self.aborted)
+            .field("truncated", &self.truncated)
+            .finish()
+    }
 }
 
 impl<const N: usize, const MAX_DEPS_TOTAL: usize> Explorer<N, MAX_DEPS_TOTAL> {
@@ -418,6 +1464,641 @@ impl<const N: usize, const MAX_DEPS_TOTAL: usize> Explorer<N, MAX_DEPS_TOTAL> {
         TopologicalIter::new(self, failed_nodes)
     }
 
+    /// Returns an iterator over every topological sort that skips `failed_nodes`.
+    pub fn all_topological_iter(
+        &self,
+        failed_nodes: &util::BitFlags,
+    ) -> Result<AllTopoIter<'_, N, MAX_DEPS_TOTAL>, ExplorerError> {
+        AllTopoIter::new(self, failed_nodes)
+    }
+
+    /// Tries every topological order on every device found on the bus and
+    /// reports which addresses accepted a complete sequence.
+    ///
+    /// Any `executor.exec` error counts as that command failing for that
+    /// address; the address is retried with the next order. Use
+    /// [`Explorer::explore_with`] for devices whose normal behavior includes
+    /// errors (e.g. a NACK on the last byte of a valid write).
+    pub fn explore<I2C, E, W, const CMD_BUFFER_SIZE: usize>(
+        &self,
+        i2c: &mut I2C,
+        executor: &mut E,
+        writer: &mut W,
+    ) -> Result<ExploreResult, ExplorerError>
+    where
+        I2C: crate::compat::I2cCompat,
+        <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+        E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+        W: core::fmt::Write,
+    {
+        self.explore_with(i2c, executor, writer, |_| false)
+    }
+
+    /// Same as [`Explorer::explore`], but `accept` decides which executor
+    /// errors are benign: when it returns `true` the command counts as
+    /// successful and the sequence continues.
+    pub fn explore_with<I2C, E, W, F, const CMD_BUFFER_SIZE: usize>(
+        &self,
+        i2c: &mut I2C,
+        executor: &mut E,
+        writer: &mut W,
+        accept: F,
+    ) -> Result<ExploreResult, ExplorerError>
+    where
+        I2C: crate::compat::I2cCompat,
+        <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+        E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+        W: core::fmt::Write,
+        F: Fn(&ExecutorError) -> bool,
+    {
+        let failed_nodes = util::BitFlags::new();
+        let mut strategy = self.all_topological_iter(&failed_nodes)?;
+        self.explore_with_strategy(
+            i2c,
+            executor,
+            writer,
+            &mut strategy,
+            accept,
+            &ExploreOptions::new(),
+        )
+    }
+
+    /// Same as [`Explorer::explore_with`], trying orders in the sequence
+    /// produced by `strategy` instead of exhaustive backtracking, within the
+    /// limits set by `options`.
+    pub fn explore_with_strategy<I2C, E, W, P, F, const CMD_BUFFER_SIZE: usize>(
+        &self,
+        i2c: &mut I2C,
+        executor: &mut E,
+        writer: &mut W,
+        strategy: &mut P,
+        accept: F,
+        options: &ExploreOptions<'_>,
+    ) -> Result<ExploreResult, ExplorerError>
+    where
+        I2C: crate::compat::I2cCompat,
+        <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+        E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+        W: core::fmt::Write,
+        P: PermutationStrategy<N>,
+        F: Fn(&ExecutorError) -> bool,
+    {
+        self.explore_with_capacity(i2c, executor, writer, strategy, accept, options)
+    }
+
+    /// Same as [`Explorer::explore_with_strategy`], keeping at most
+    /// `MAX_FOUND` solved addresses so the result fits small stacks. The
+    /// capacity is usually inferred from the binding:
+    /// `let result: ExploreResult<4> = explorer.explore_with_capacity(...)?;`.
+    ///
+    /// Fails with [`ExplorerError::BufferOverflow`] if more than `MAX_FOUND`
+    /// devices accept the sequence.
+    pub fn explore_with_capacity<
+        I2C,
+        E,
+        W,
+        P,
+        F,
+        const CMD_BUFFER_SIZE: usize,
+        const MAX_FOUND: usize,
+    >(
+        &self,
+        i2c: &mut I2C,
+        executor: &mut E,
+        writer: &mut W,
+        strategy: &mut P,
+        accept: F,
+        options: &ExploreOptions<'_>,
+    ) -> Result<ExploreResult<MAX_FOUND>, ExplorerError>
+    where
+        I2C: crate::compat::I2cCompat,
+        <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+        E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+        W: core::fmt::Write,
+        P: PermutationStrategy<N>,
+        F: Fn(&ExecutorError) -> bool,
+    {
+        self.explore_impl::<_, _, _, _, _, CMD_BUFFER_SIZE, MAX_FOUND, 2>(
+            i2c, executor, writer, strategy, accept, options, None,
+        )
+    }
+
+    /// Same as [`Explorer::explore_with_capacity`], but remembers in `cache`
+    /// the order prefixes that failed on each address, and skips later
+    /// orders starting with a known-bad prefix on that address without
+    /// sending anything.
+    ///
+    /// This assumes a device answers a given prefix the same way every time,
+    /// whatever the previous order left it in; it saves the repeated doomed
+    /// writes of devices that reject early commands. A full cache stops
+    /// learning but keeps skipping. The cache can be reused across runs
+    /// against the same devices.
+    #[allow(clippy::too_many_arguments)]
+    pub fn explore_with_cache<
+        I2C,
+        E,
+        W,
+        P,
+        F,
+        const CMD_BUFFER_SIZE: usize,
+        const MAX_FOUND: usize,
+        const CACHE: usize,
+    >(
+        &self,
+        i2c: &mut I2C,
+        executor: &mut E,
+        writer: &mut W,
+        strategy: &mut P,
+        accept: F,
+        options: &ExploreOptions<'_>,
+        cache: &mut FailedPrefixCache<CACHE>,
+    ) -> Result<ExploreResult<MAX_FOUND>, ExplorerError>
+    where
+        I2C: crate::compat::I2cCompat,
+        <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+        E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+        W: core::fmt::Write,
+        P: PermutationStrategy<N>,
+        F: Fn(&ExecutorError) -> bool,
+    {
+        self.explore_impl(
+            i2c,
+            executor,
+            writer,
+            strategy,
+            accept,
+            options,
+            Some(cache),
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn explore_impl<
+        I2C,
+        E,
+        W,
+        P,
+        F,
+        const CMD_BUFFER_SIZE: usize,
+        const MAX_FOUND: usize,
+        const CACHE: usize,
+    >(
+        &self,
+        i2c: &mut I2C,
+        executor: &mut E,
+        writer: &mut W,
+        strategy: &mut P,
+        accept: F,
+        options: &ExploreOptions<'_>,
+        mut cache: Option<&mut FailedPrefixCache<CACHE>>,
+    ) -> Result<ExploreResult<MAX_FOUND>, ExplorerError>
+    where
+        I2C: crate::compat::I2cCompat,
+        <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+        E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+        W: core::fmt::Write,
+        P: PermutationStrategy<N>,
+        F: Fn(&ExecutorError) -> bool,
+    {
+        let candidates = match crate::scanner::internal_scan_flags(i2c) {
+            Ok(addrs) => addrs,
+            Err(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)) => {
+                return Err(ExplorerError::NoValidAddressesFound);
+            }
+            Err(e) => return Err(ExplorerError::DeviceNotFound(e)),
+        };
+
+        let candidate_count = candidates.count_ones() as usize;
+        // Only counted when progress is logged; `None` if too many to count.
+        let total_orders = options
+            .progress_every
+            .and_then(|_| self.count_orders(ESTIMATE_ORDER_LIMIT))
+            .filter(|&total| total > 0 && total <= ESTIMATE_ORDER_LIMIT);
+        let mut solved = util::BitFlags::new();
+        let mut writes = 0usize;
+        let mut result = ExploreResult {
+            found_addrs: [0; MAX_FOUND],
+            found_addrs_len: 0,
+            permutations_tested: 0,
+            aborted: false,
+            truncated: false,
+        };
+
+        'orders: while let Some(order) = strategy.next_order() {
+            if options.is_aborted() {
+                result.aborted = true;
+                break;
+            }
+            if !self.is_valid_order(&order) {
+                dvcdbg_log!(writer, "[explore] Skipping invalid order: ");
+                dvcdbg_log!(@hex writer, &order);
+                dvcdbg_log!(writer, "\r\n");
+                continue;
+            }
+            if options
+                .max_permutations
+                .is_some_and(|max| result.permutations_tested >= max)
+            {
+                dvcdbg_log!(
+                    writer,
+                    "[explore] Stopped after {} orders, search truncated\r\n",
+                    result.permutations_tested
+                );
+                result.truncated = true;
+                break;
+            }
+            result.permutations_tested += 1;
+            dvcdbg_log!(writer, "[explore] Order {}: ", result.permutations_tested);
+            dvcdbg_log!(@hex writer, &order);
+            dvcdbg_log!(writer, "\r\n");
+
+            for addr in candidates.iter_set() {
+                let addr = addr as u8;
+                if solved.get(addr as usize).unwrap_or(true) {
+                    continue;
+                }
+                if let Some(cache) = cache.as_deref()
+                    && let Some(len) = cache.known_bad_prefix(addr, &order)
+                {
+                    dvcdbg_log!(
+                        writer,
+                        "[explore] Skipping @ {addr:02X}, first {len} commands known to fail\r\n"
+                    );
+                    continue;
+                }
+                let mut complete = true;
+                for (pos, &idx) in order.iter().enumerate() {
+                    if options.is_aborted() {
+                        result.aborted = true;
+                        break 'orders;
+                    }
+                    if options.max_writes.is_some_and(|max| writes >= max) {
+                        dvcdbg_log!(writer, "[explore] Write budget of {writes} exhausted\r\n");
+                        return Err(ExplorerError::WriteBudgetExhausted);
+                    }
+                    writes += 1;
+                    let node = &self.nodes[idx as usize];
+                    match executor.exec(i2c, addr, node.bytes, writer) {
+                        Ok(()) => {
+                            if node.delay_us > 0 {
+                                executor.pause_us(node.delay_us);
+                            }
+                        }
+                        Err(e) if accept(&e) => {
+                            dvcdbg_log!(writer, "[explore] Accepted error on {idx}: {e}\r\n");
+                        }
+                        Err(_) => {
+                            if let Some(cache) = cache.as_deref_mut() {
+                                cache.insert(addr, &order[..=pos]);
+                            }
+                            complete = false;
+                            break;
+                        }
+                    }
+                }
+                if complete {
+                    dvcdbg_log!(writer, "[explore] Sequence OK @ {addr:02X}\r\n");
+                    solved.set(addr as usize).map_err(ExplorerError::BitFlags)?;
+                    let Some(slot) = result.found_addrs.get_mut(result.found_addrs_len) else {
+                        return Err(ExplorerError::BufferOverflow);
+                    };
+                    *slot = addr;
+                    result.found_addrs_len += 1;
+                }
+            }
+
+            if let Some(every) = options.progress_every
+                && result.permutations_tested.is_multiple_of(every)
+            {
+                let tested = result.permutations_tested;
+                if let Some(total) = total_orders {
+                    dvcdbg_log!(
+                        writer,
+                        "[explore] Progress: {tested}/{total} orders ({}%), {}/{} devices solved\r\n",
+                        tested as u64 * 100 / total,
+                        result.found_addrs_len,
+                        candidate_count
+                    );
+                } else {
+                    dvcdbg_log!(
+                        writer,
+                        "[explore] Progress: {tested} orders, {}/{} devices solved\r\n",
+                        result.found_addrs_len,
+                        candidate_count
+                    );
+                }
+            }
+
+            if result.found_addrs_len == candidate_count {
+                break;
+            }
+        }
+        if result.aborted {
+            dvcdbg_log!(writer, "[explore] Aborted\r\n");
+            return Ok(result);
+        }
+        if strategy.is_cycle_detected() {
+            dvcdbg_log!(writer, "[error] Dependency cycle detected!\r\n");
+            return Err(ExplorerError::DependencyCycle);
+        }
+
+        Ok(result)
+    }
+
+    /// Rough worst-case cost of [`Explorer::explore`], in cycles: every
+    /// topological order run in full on `addrs` devices at `per_cmd_cycles`
+    /// per command.
+    ///
+    /// Orders are counted by enumeration; beyond `ESTIMATE_ORDER_LIMIT` orders
+    /// the search is considered infeasible and the estimate saturates to
+    /// `u64::MAX`. Returns `None` if the graph is invalid or cyclic.
+    pub fn estimate_runtime(&self, per_cmd_cycles: u32, addrs: usize) -> Option<u64> {
+        let orders = self.count_orders(ESTIMATE_ORDER_LIMIT)?;
+        if orders > ESTIMATE_ORDER_LIMIT {
+            return Some(u64::MAX);
+        }
+        Some(
+            orders
+                .saturating_mul(self.nodes.len() as u64)
+                .saturating_mul(addrs as u64)
+                .saturating_mul(per_cmd_cycles as u64),
+        )
+    }
+
+    /// Counts the valid orders (linear extensions of the dependency graph)
+    /// without enumerating them. `failed_nodes` are skipped as in
+    /// [`Explorer::all_topological_iter`], which would yield exactly this many
+    /// orders. Saturates at `usize::MAX`.
+    ///
+    /// The count is a depth-first search memoized on the set of placed nodes,
+    /// so it visits each reachable set once instead of each order: a
+    /// near-linear dependency chain returns 1 almost immediately. The number
+    /// of orders explodes combinatorially for loosely constrained graphs (`n!`
+    /// for `n` independent nodes), and so does the number of reachable sets,
+    /// only slower (`2^n`). The memo holds 256 sets, enough for about 8
+    /// independent nodes; a graph with more reachable sets fails with
+    /// [`ExplorerError::BufferOverflow`] rather than falling back to
+    /// enumeration. Use [`Explorer::count_topological_sorts_with_memo`] for a
+    /// larger memo.
+    pub fn count_topological_sorts(
+        &self,
+        failed_nodes: &util::BitFlags,
+    ) -> Result<usize, ExplorerError> {
+        self.count_topological_sorts_with_memo::<COUNT_MEMO_CAPACITY>(failed_nodes)
+    }
+
+    /// Same as [`Explorer::count_topological_sorts`] with a memo of `MEMO`
+    /// placed-node sets (a power of two), e.g. 4096 for 12 independent nodes.
+    pub fn count_topological_sorts_with_memo<const MEMO: usize>(
+        &self,
+        failed_nodes: &util::BitFlags,
+    ) -> Result<usize, ExplorerError> {
+        let len = self.nodes.len();
+        if len > N || len > util::BitFlags::N_BITS {
+            return Err(ExplorerError::TooManyCommands);
+        }
+
+        let skipped = failed_nodes.to_u128();
+        let mut graph = OrderGraph {
+            deps: [0; N],
+            placements: [Placement::Any; N],
+            first: 0,
+            any: 0,
+            target: 0,
+            len,
+        };
+        for (i, node) in self.nodes.iter().enumerate() {
+            for &dep in node.deps.iter() {
+                if dep as usize >= len {
+                    return Err(ExplorerError::InvalidDependencyIndex);
+                }
+                graph.deps[i] |= 1 << dep;
+            }
+            graph.placements[i] = node.placement;
+            if skipped & (1 << i) != 0 {
+                continue;
+            }
+            graph.target |= 1 << i;
+            match node.placement {
+                Placement::First => graph.first |= 1 << i,
+                Placement::Any => graph.any |= 1 << i,
+                Placement::Last => {}
+            }
+        }
+
+        let mut memo = heapless::index_map::FnvIndexMap::<u128, usize, MEMO>::new();
+        match graph.count_from(0, &mut memo) {
+            None => Err(ExplorerError::BufferOverflow),
+            Some(0) if graph.target != 0 => Err(ExplorerError::DependencyCycle),
+            Some(count) => Ok(count),
+        }
+    }
+
+    /// Counts topological orders, stopping once `limit` is exceeded
+    /// (returns `limit + 1` then). `None` for invalid or cyclic graphs.
+    fn count_orders(&self, limit: u64) -> Option<u64> {
+        let failed_nodes = util::BitFlags::new();
+        let mut iter = self.all_topological_iter(&failed_nodes).ok()?;
+        let mut count = 0u64;
+        for _ in iter.by_ref() {
+            count += 1;
+            if count > limit {
+                return Some(count);
+            }
+        }
+        if iter.is_cycle_detected() {
+            return None;
+        }
+        Some(count)
+    }
+
+    /// Checks that `order` lists every node exactly once and never runs a
+    /// node before its dependencies.
+    pub fn is_valid_order(&self, order: &[u8]) -> bool {
+        if order.len() != self.nodes.len() {
+            return false;
+        }
+        let mut done = util::BitFlags::new();
+        let mut gate = PlacementGate::new(self.nodes, |_| false);
+        for &idx in order {
+            let idx = idx as usize;
+            let Some(node) = self.nodes.get(idx) else {
+                return false;
+            };
+            if done.get(idx).unwrap_or(true)
+                || !gate.allows(node.placement)
+                || !node
+                    .deps
+                    .iter()
+                    .all(|&d| done.get(d as usize).unwrap_or(false))
+            {
+                return false;
+            }
+            done.set(idx).ok();
+            gate.take(node.placement);
+        }
+        true
+    }
+
+    /// Finds nodes whose bytes are identical to an earlier node, usually a
+    /// copy-paste mistake in the graph.
+    ///
+    /// Each duplicate is paired with the first node it repeats, so
+    /// `[A, B, A, A]` gives `(0, 2)` and `(0, 3)`, and the result always fits.
+    ///
+    /// ```ignore
+    /// for (a, b) in EXPLORER.find_duplicate_commands() {
+    ///     writeln!(serial, "nodes {a} and {b} are identical").ok();
+    /// }
+    /// ```
+    pub fn find_duplicate_commands(&self) -> heapless::Vec<(usize, usize), N> {
+        let mut pairs = heapless::Vec::new();
+        for (j, node) in self.nodes.iter().enumerate().skip(1) {
+            if let Some(i) = self.nodes[..j].iter().position(|n| n.bytes == node.bytes) {
+                // At most one pair per node, so this never exceeds N.
+                pairs.push((i, j)).ok();
+            }
+        }
+        pairs
+    }
+
+    /// The nodes that can run once `node` has executed on top of
+    /// `already_done`: every node outside `already_done ∪ {node}` whose
+    /// dependencies all lie in that set.
+    ///
+    /// Meant for stepping through the graph one command at a time, listing
+    /// the possible next commands after each step.
+    ///
+    /// ```ignore
+    /// let mut done = BitFlags::new();
+    /// let next = EXPLORER.unlocked_by(0, &done); // choices after node 0
+    /// done.set(0)?;
+    /// ```
+    pub fn unlocked_by(
+        &self,
+        node: usize,
+        already_done: &util::BitFlags,
+    ) -> heapless::Vec<usize, N> {
+        let is_done = |idx: usize| idx == node || already_done.get(idx).unwrap_or(false);
+        let mut ready = heapless::Vec::new();
+        for (idx, candidate) in self.nodes.iter().enumerate() {
+            if is_done(idx) {
+                continue;
+            }
+            if candidate.deps.iter().all(|&dep| is_done(dep as usize)) {
+                // At most one entry per node, so this never exceeds N.
+                ready.push(idx).ok();
+            }
+        }
+        ready
+    }
+
+    /// Finds the first dependency that points past the last node, as
+    /// `(node_index, bad_dep)`.
+    ///
+    /// Iteration reports such a dep only as `InvalidDependencyIndex`, without
+    /// saying where it is. Being `const`, the check can also run at compile
+    /// time:
+    ///
+    /// ```ignore
+    /// const _: () = assert!(EXPLORER.check_dep_bounds().is_ok());
+    /// ```
+    pub const fn check_dep_bounds(&self) -> Result<(), (usize, DepIndex)> {
+        let mut i = 0;
+        while i < self.nodes.len() {
+            let deps = self.nodes[i].deps;
+            let mut j = 0;
+            while j < deps.len() {
+                if deps[j] as usize >= self.nodes.len() {
+                    return Err((i, deps[j]));
+                }
+                j += 1;
+            }
+            i += 1;
+        }
+        Ok(())
+    }
+
+    /// Computes each node's depth: the length of the longest dependency path
+    /// leading to it from a root (a node without dependencies).
+    pub fn node_depths(&self) -> Result<heapless::Vec<u16, N>, ExplorerError> {
+        let mut depths = heapless::Vec::<u16, N>::new();
+        depths
+            .resize(self.nodes.len(), 0)
+            .map_err(|_| ExplorerError::TooManyCommands)?;
+
+        let failed_nodes = util::BitFlags::new();
+        let mut sort_iter = self.topological_iter(&failed_nodes)?;
+        for idx in sort_iter.by_ref() {
+            // Dependencies come earlier in the sort, so their depths are final.
+            depths[idx] = self.nodes[idx]
+                .deps
+                .iter()
+                .map(|&dep| depths[dep as usize] + 1)
+                .max()
+                .unwrap_or(0);
+        }
+        if sort_iter.is_cycle_detected() {
+            return Err(ExplorerError::DependencyCycle);
+        }
+
+        Ok(depths)
+    }
+
+    /// Writes the nodes grouped by depth level, one line per level.
+    ///
+    /// ```text
+    /// L0: #0[AE] #1[D5 80]
+    /// L1: #2[A8 3F]
+    /// ```
+    pub fn write_graph_leveled<W: core::fmt::Write>(
+        &self,
+        writer: &mut W,
+    ) -> Result<(), ExplorerError> {
+        let depths = self.node_depths()?;
+        let max_depth = depths.iter().copied().max().unwrap_or(0);
+
+        for level in 0..=max_depth {
+            write!(writer, "L{level}:").ok();
+            for (idx, _) in depths.iter().enumerate().filter(|(_, d)| **d == level) {
+                write!(writer, " #{idx}[").ok();
+                util::write_bytes_hex_fmt(writer, self.nodes[idx].bytes).ok();
+                writer.write_str("]").ok();
+            }
+            writer.write_str("\r\n").ok();
+        }
+
+        Ok(())
+    }
+
+    /// Writes the dependency graph in Graphviz DOT, one `dep -> node` edge per
+    /// dependency and each node labeled with its bytes.
+    ///
+    /// Works on any graph, cyclic or not, so a `DependencyCycle` can be spotted
+    /// by pasting the output into a DOT viewer.
+    ///
+    /// ```text
+    /// digraph {
+    ///   0 [label="#0: AE"];
+    ///   1 [label="#1: D5 80"];
+    ///   0 -> 1;
+    /// }
+    /// ```
+    pub fn write_dot<W: core::fmt::Write>(&self, w: &mut W) -> core::fmt::Result {
+        w.write_str("digraph {\r\n")?;
+        for (idx, node) in self.nodes.iter().enumerate() {
+            write!(w, "  {idx} [label=\"#{idx}: ")?;
+            util::write_bytes_hex_fmt(w, node.bytes)?;
+            w.write_str("\"];\r\n")?;
+        }
+        for (idx, node) in self.nodes.iter().enumerate() {
+            for dep in node.deps.iter() {
+                write!(w, "  {dep} -> {idx};\r\n")?;
+            }
+        }
+        w.write_str("}\r\n")
+    }
+
     pub const fn max_cmd_len(&self) -> usize {
         let mut max_len = 0;
         let mut i = 0;
@@ -435,3 +2116,195 @@ impl<const N: usize, const MAX_DEPS_TOTAL: usize> Explorer<N, MAX_DEPS_TOTAL> {
         Self { nodes }
     }
 }
+
+/// A command graph the runners can execute: a static [`Explorer`], a
+/// runtime-editable [`MutableExplorer`] or a runtime-built [`RuntimeExplorer`].
+pub trait NodeSet<const N: usize, const MAX_DEPS_TOTAL: usize> {
+    /// All nodes, including disabled ones; dependency indices refer to this slice.
+    fn nodes(&self) -> &[CmdNode<'_>];
+
+    /// Node `idx`, or `InvalidDependencyIndex` if it is out of range. Runners
+    /// look nodes up through this so a bad index fails instead of panicking.
+    fn node(&self, idx: usize) -> Result<&CmdNode<'_>, ExplorerError> {
+        self.nodes()
+            .get(idx)
+            .ok_or(ExplorerError::InvalidDependencyIndex)
+    }
+
+    /// Nodes excluded from execution. Their dependents still run, as if the
+    /// dependency were satisfied.
+    fn disabled_nodes(&self) -> util::BitFlags {
+        util::BitFlags::new()
+    }
+
+    fn topological_iter(
+        &self,
+        failed_nodes: &util::BitFlags,
+    ) -> Result<TopologicalIter<'_, N, MAX_DEPS_TOTAL>, ExplorerError> {
+        TopologicalIter::from_nodes(self.nodes(), failed_nodes, &self.disabled_nodes())
+    }
+
+    fn all_topological_iter(
+        &self,
+        failed_nodes: &util::BitFlags,
+    ) -> Result<AllTopoIter<'_, N, MAX_DEPS_TOTAL>, ExplorerError> {
+        AllTopoIter::from_nodes(self.nodes(), failed_nodes, &self.disabled_nodes())
+    }
+}
+
+impl<const N: usize, const MAX_DEPS_TOTAL: usize> NodeSet<N, MAX_DEPS_TOTAL>
+    for Explorer<N, MAX_DEPS_TOTAL>
+{
+    fn nodes(&self) -> &[CmdNode<'_>] {
+        self.nodes
+    }
+}
+
+/// An [`Explorer`] with an owned copy of the nodes, so individual commands can
+/// be switched off at runtime (e.g. a command known to misbehave on one unit).
+///
+/// ```ignore
+/// let mut explorer = MutableExplorer::<N, MAX_DEPS>::from_explorer(&EXPLORER)?;
+/// explorer.disable_node(4)?;
+/// pruning_sort!(&explorer, &mut i2c, &mut serial, PREFIX, N, BUF, MAX_DEPS)?;
+/// ```
+pub struct MutableExplorer<const N: usize, const MAX_DEPS_TOTAL: usize> {
+    nodes: [CmdNode<'static>; N],
+    len: usize,
+    disabled: util::BitFlags,
+}
+
+impl<const N: usize, const MAX_DEPS_TOTAL: usize> MutableExplorer<N, MAX_DEPS_TOTAL> {
+    /// Copies `nodes`, all enabled. Fails if there are more than `N`.
+    pub fn new(nodes: &[CmdNode<'static>]) -> Result<Self, ExplorerError> {
+        if nodes.len() > N {
+            return Err(ExplorerError::TooManyCommands);
+        }
+        let mut owned = [CmdNode::new(&[], &[]); N];
+        owned[..nodes.len()].copy_from_slice(nodes);
+
+        Ok(Self {
+            nodes: owned,
+            len: nodes.len(),
+            disabled: util::BitFlags::new(),
+        })
+    }
+
+    /// Copies the node set of a static explorer.
+    pub fn from_explorer(explorer: &Explorer<N, MAX_DEPS_TOTAL>) -> Result<Self, ExplorerError> {
+        Self::new(explorer.nodes)
+    }
+
+    /// Excludes node `idx` from execution.
+    pub fn disable_node(&mut self, idx: usize) -> Result<(), ExplorerError> {
+        self.check_index(idx)?;
+        self.disabled.set(idx).map_err(ExplorerError::BitFlags)
+    }
+
+    /// Re-includes a node previously excluded with [`Self::disable_node`].
+    pub fn enable_node(&mut self, idx: usize) -> Result<(), ExplorerError> {
+        self.check_index(idx)?;
+        self.disabled.clear(idx).map_err(ExplorerError::BitFlags)
+    }
+
+    pub fn is_enabled(&self, idx: usize) -> bool {
+        idx < self.len && !self.disabled.get(idx).unwrap_or(true)
+    }
+
+    fn check_index(&self, idx: usize) -> Result<(), ExplorerError> {
+        if idx < self.len {
+            Ok(())
+        } else {
+            Err(ExplorerError::BitFlags(BitFlagsError::IndexOutOfBounds {
+                idx,
+                max: self.len.saturating_sub(1),
+            }))
+        }
+    }
+}
+
+impl<const N: usize, const MAX_DEPS_TOTAL: usize> NodeSet<N, MAX_DEPS_TOTAL>
+    for MutableExplorer<N, MAX_DEPS_TOTAL>
+{
+    fn nodes(&self) -> &[CmdNode<'_>] {
+        &self.nodes[..self.len]
+    }
+
+    fn disabled_nodes(&self) -> util::BitFlags {
+        self.disabled
+    }
+}
+
+/// A command node that owns its bytes, for sequences built at runtime (e.g.
+/// read from an EEPROM config) rather than declared as `static` data.
+///
+/// Up to `MAX_BYTES` command bytes and `MAX_NODE_DEPS` dependencies. Borrow a
+/// slice of them with [`RuntimeExplorer::new`] to run the usual runners.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct OwnedCmdNode<const MAX_BYTES: usize, const MAX_NODE_DEPS: usize> {
+    pub bytes: heapless::Vec<u8, MAX_BYTES>,
+    pub deps: heapless::Vec<DepIndex, MAX_NODE_DEPS>,
+    pub placement: Placement,
+    pub delay_us: u32,
+}
+
+impl<const MAX_BYTES: usize, const MAX_NODE_DEPS: usize> OwnedCmdNode<MAX_BYTES, MAX_NODE_DEPS> {
+    /// Copies `bytes` and `deps`; fails with `BufferOverflow` if either does
+    /// not fit.
+    pub fn new(bytes: &[u8], deps: &[DepIndex]) -> Result<Self, ExplorerError> {
+        Ok(Self {
+            bytes: heapless::Vec::from_slice(bytes).map_err(|_| ExplorerError::BufferOverflow)?,
+            deps: heapless::Vec::from_slice(deps).map_err(|_| ExplorerError::BufferOverflow)?,
+            placement: Placement::Any,
+            delay_us: 0,
+        })
+    }
+
+    /// The node as a [`CmdNode`] borrowing its bytes.
+    pub fn as_node(&self) -> CmdNode<'_> {
+        CmdNode {
+            bytes: &self.bytes,
+            deps: &self.deps,
+            placement: self.placement,
+            delay_us: self.delay_us,
+        }
+    }
+}
+
+/// An explorer over runtime-built [`OwnedCmdNode`]s.
+///
+/// Works like [`Explorer`] with every runner, through [`NodeSet`]; only the
+/// nodes are borrowed for `'a` instead of being `'static`.
+///
+/// ```ignore
+/// let mut nodes: heapless::Vec<OwnedCmdNode<4, 2>, 8> = heapless::Vec::new();
+/// nodes.push(OwnedCmdNode::new(&eeprom_cmd, &[])?).ok();
+/// let explorer = RuntimeExplorer::<8, 8>::new(&nodes)?;
+/// pruning_sort!(&explorer, &mut i2c, &mut serial, PREFIX, 8, BUF, 8)?;
+/// ```
+pub struct RuntimeExplorer<'a, const N: usize, const MAX_DEPS_TOTAL: usize> {
+    nodes: heapless::Vec<CmdNode<'a>, N>,
+}
+
+impl<'a, const N: usize, const MAX_DEPS_TOTAL: usize> RuntimeExplorer<'a, N, MAX_DEPS_TOTAL> {
+    /// Borrows `nodes`. Fails if there are more than `N`.
+    pub fn new<const MAX_BYTES: usize, const MAX_NODE_DEPS: usize>(
+        nodes: &'a [OwnedCmdNode<MAX_BYTES, MAX_NODE_DEPS>],
+    ) -> Result<Self, ExplorerError> {
+        let mut borrowed = heapless::Vec::new();
+        for node in nodes {
+            borrowed
+                .push(node.as_node())
+                .map_err(|_| ExplorerError::TooManyCommands)?;
+        }
+        Ok(Self { nodes: borrowed })
+    }
+}
+
+impl<const N: usize, const MAX_DEPS_TOTAL: usize> NodeSet<N, MAX_DEPS_TOTAL>
+    for RuntimeExplorer<'_, N, MAX_DEPS_TOTAL>
+{
+    fn nodes(&self) -> &[CmdNode<'_>] {
+        &self.nodes
+    }
+}
@@ -1,3 +1,6 @@
+pub mod event;
 pub mod explorer;
 // pub mod logger;
+pub mod program;
 pub mod runner;
+pub mod trace;
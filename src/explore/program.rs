@@ -0,0 +1,171 @@
+// program.rs
+
+use crate::compat::HalErrorExt;
+use crate::compat::I2cCompat;
+use crate::compat::delay_compat::DelayCompat;
+use crate::compat::util;
+use crate::error::ExplorerError;
+use crate::explore::explorer::{CmdExecutor, NodeSet, PrefixExecutor, exec_log_cmd};
+
+/// A working init sequence for one device: its address, the prefix byte and
+/// the command frames in the order that succeeded, each with the pause that
+/// follows it.
+///
+/// This is the reusable output of exploration. Frames borrow the bytes of the
+/// command nodes (`'static` for an [`Explorer`](crate::explore::explorer::Explorer)),
+/// so a program is cheap to keep around and can
+/// be replayed at every boot, or dumped with [`InitProgram::write_hex`] to be
+/// pasted into firmware.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InitProgram<'a, const N: usize, const CMD_BUFFER_SIZE: usize> {
+    addr: u8,
+    prefix: u8,
+    frames: heapless::Vec<&'a [u8], N>,
+    /// Pause after each frame, in microseconds ([`CmdNode::delay_us`](crate::explore::explorer::CmdNode::delay_us)).
+    delays: heapless::Vec<u32, N>,
+}
+
+impl<'a, const N: usize, const CMD_BUFFER_SIZE: usize> InitProgram<'a, N, CMD_BUFFER_SIZE> {
+    /// An empty program for `addr`.
+    pub fn new(addr: u8, prefix: u8) -> Self {
+        Self {
+            addr,
+            prefix,
+            frames: heapless::Vec::new(),
+            delays: heapless::Vec::new(),
+        }
+    }
+
+    /// Builds the program from node indices of `explorer`, in `order`.
+    pub fn from_order<X, const MAX_DEPS: usize>(
+        explorer: &'a X,
+        addr: u8,
+        prefix: u8,
+        order: &[u8],
+    ) -> Result<Self, ExplorerError>
+    where
+        X: NodeSet<N, MAX_DEPS>,
+    {
+        let mut program = Self::new(addr, prefix);
+        for &idx in order {
+            let node = explorer
+                .nodes()
+                .get(idx as usize)
+                .ok_or(ExplorerError::InvalidDependencyIndex)?;
+            program.push_with_delay(node.bytes, node.delay_us)?;
+        }
+        Ok(program)
+    }
+
+    /// Builds the program from the single topological sort the runners use.
+    pub fn first_sort<X, const MAX_DEPS: usize>(
+        explorer: &'a X,
+        addr: u8,
+        prefix: u8,
+    ) -> Result<Self, ExplorerError>
+    where
+        X: NodeSet<N, MAX_DEPS>,
+    {
+        let failed_nodes = util::BitFlags::new();
+        let mut sort_iter = explorer.topological_iter(&failed_nodes)?;
+        let mut program = Self::new(addr, prefix);
+        for idx in sort_iter.by_ref() {
+            let node = explorer.node(idx)?;
+            program.push_with_delay(node.bytes, node.delay_us)?;
+        }
+        if sort_iter.is_cycle_detected() {
+            return Err(ExplorerError::DependencyCycle);
+        }
+        Ok(program)
+    }
+
+    /// Appends a command frame (without the prefix byte).
+    pub fn push(&mut self, frame: &'a [u8]) -> Result<(), ExplorerError> {
+        self.push_with_delay(frame, 0)
+    }
+
+    /// Appends a command frame followed by a pause of `delay_us` microseconds.
+    pub fn push_with_delay(&mut self, frame: &'a [u8], delay_us: u32) -> Result<(), ExplorerError> {
+        self.frames
+            .push(frame)
+            .map_err(|_| ExplorerError::TooManyCommands)?;
+        // Cannot overflow: `delays` always has as many entries as `frames`.
+        self.delays.push(delay_us).ok();
+        Ok(())
+    }
+
+    pub fn addr(&self) -> u8 {
+        self.addr
+    }
+
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    /// The command frames, in execution order.
+    pub fn frames(&self) -> &[&'a [u8]] {
+        &self.frames
+    }
+
+    /// The pause after each frame of [`InitProgram::frames`], in microseconds.
+    pub fn delays(&self) -> &[u32] {
+        &self.delays
+    }
+
+    /// Sends every frame to the program's address, prefixed and with retry,
+    /// stopping at the first failure. Returns the number of frames sent.
+    ///
+    /// `delay` times the pauses recorded with the frames, as well as the
+    /// executor's retry and post-write pauses.
+    pub fn replay<I2C, S, D>(
+        &self,
+        i2c: &mut I2C,
+        serial: &mut S,
+        delay: D,
+    ) -> Result<usize, ExplorerError>
+    where
+        I2C: I2cCompat,
+        <I2C as I2cCompat>::Error: HalErrorExt,
+        S: core::fmt::Write,
+        D: DelayCompat,
+    {
+        dvcdbg_log!(
+            serial,
+            "[program] {} frames to {:02X}\r\n",
+            self.frames.len(),
+            self.addr
+        );
+        let mut executor =
+            PrefixExecutor::<0, CMD_BUFFER_SIZE>::new(self.prefix, &[]).with_delay(delay);
+        for (idx, (frame, &delay_us)) in self.frames.iter().zip(self.delays.iter()).enumerate() {
+            exec_log_cmd::<_, _, _, CMD_BUFFER_SIZE>(
+                i2c,
+                &mut executor,
+                serial,
+                self.addr,
+                frame,
+                idx,
+            )?;
+            if delay_us > 0 {
+                CmdExecutor::<I2C, CMD_BUFFER_SIZE>::pause_us(&mut executor, delay_us);
+            }
+        }
+        Ok(self.frames.len())
+    }
+
+    /// Writes the program as one prefixed hex frame per line, e.g. `3C: 00 AE`,
+    /// followed by ` +1000us` when the frame has a pause.
+    pub fn write_hex<W: core::fmt::Write>(&self, writer: &mut W) -> core::fmt::Result {
+        for (frame, &delay_us) in self.frames.iter().zip(self.delays.iter()) {
+            write!(writer, "{:02X}: {:02X}", self.addr, self.prefix)?;
+            for b in frame.iter() {
+                write!(writer, " {b:02X}")?;
+            }
+            if delay_us > 0 {
+                write!(writer, " +{delay_us}us")?;
+            }
+            writer.write_str("\r\n")?;
+        }
+        Ok(())
+    }
+}
@@ -1,39 +1,162 @@
 // runner.rs
 
 use crate::compat::util;
+use crate::compat::{HalErrorExt, TimeSource};
 use crate::error::ExplorerError;
+use crate::explore::event::{Event, EventSink, EventWriter};
 use crate::explore::explorer::*;
+use crate::explore::program::InitProgram;
 use crate::scanner::I2C_MAX_DEVICES;
+use core::sync::atomic::{AtomicBool, Ordering};
 
 #[macro_export]
 macro_rules! pruning_sort {
+    (graph = $graph:ident, $i2c:expr, $serial:expr) => {
+        $crate::explore::runner::pruning_explorer::<
+            _,
+            _,
+            _,
+            { $graph::N },
+            { $graph::BATCH_BUFFER_SIZE },
+            { $graph::MAX_DEPS },
+        >(&$graph::EXPLORER, $i2c, $serial, $graph::PREFIX)
+    };
+    (graph = $graph:ident, $i2c:expr, $serial:expr, $max_orders:expr) => {
+        $crate::explore::runner::pruning_explorer_with_retries::<
+            _,
+            _,
+            _,
+            { $graph::N },
+            { $graph::BATCH_BUFFER_SIZE },
+            { $graph::MAX_DEPS },
+        >(
+            &$graph::EXPLORER,
+            $i2c,
+            $serial,
+            $graph::PREFIX,
+            $max_orders,
+        )
+    };
     ($explorer:expr, $i2c:expr, $serial:expr, $prefix:expr, $n:expr, $cmd_buf:expr, $max_deps:expr) => {
-        $crate::explore::runner::pruning_explorer::<_, _, $n, $cmd_buf, $max_deps>(
+        $crate::explore::runner::pruning_explorer::<_, _, _, $n, $cmd_buf, $max_deps>(
             $explorer, $i2c, $serial, $prefix,
         )
     };
+    ($explorer:expr, $i2c:expr, $serial:expr, $prefix:expr, $n:expr, $cmd_buf:expr, $max_deps:expr, $max_orders:expr) => {
+        $crate::explore::runner::pruning_explorer_with_retries::<_, _, _, $n, $cmd_buf, $max_deps>(
+            $explorer,
+            $i2c,
+            $serial,
+            $prefix,
+            $max_orders,
+        )
+    };
 }
 
+/// Runs one batched init sequence per device found on the bus, pruning the
+/// nodes of devices whose batch fails.
+///
+/// Each address gets a single topological order; see
+/// [`pruning_explorer_with_retries`] to retry failing addresses with other
+/// orders.
 pub fn pruning_explorer<
     I2C,
     S,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    prefix: u8,
+) -> Result<(), ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    pruning_explorer_with_retries::<_, _, _, N, CMD_BUFFER_SIZE, MAX_DEPS>(
+        explorer, i2c, serial, prefix, 1,
+    )
+}
+
+/// Same as [`pruning_explorer`], but a failing address is retried with up to
+/// `max_orders_per_addr` distinct topological orders in total before its
+/// nodes are pruned.
+///
+/// A failure may depend on the order of the commands rather than on the
+/// commands themselves. `1` (or `0`) keeps the single-order behavior.
+pub fn pruning_explorer_with_retries<
+    I2C,
+    S,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    prefix: u8,
+    max_orders_per_addr: usize,
+) -> Result<(), ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    let never = AtomicBool::new(false);
+    pruning_explorer_with_abort::<_, _, _, N, CMD_BUFFER_SIZE, MAX_DEPS>(
+        explorer,
+        i2c,
+        serial,
+        prefix,
+        max_orders_per_addr,
+        &never,
+    )
+}
+
+/// Same as [`pruning_explorer_with_retries`], but stops with [`ExplorerError::Aborted`]
+/// once `abort` is set (e.g. from an ISR).
+///
+/// The flag is polled before each address and each order attempt, so the
+/// bus is never left in the middle of a batch.
+pub fn pruning_explorer_with_abort<
+    I2C,
+    S,
+    X,
     const N: usize,
     const CMD_BUFFER_SIZE: usize,
     const MAX_DEPS: usize,
 >(
-    explorer: &Explorer<N, MAX_DEPS>,
+    explorer: &X,
     i2c: &mut I2C,
     serial: &mut S,
     prefix: u8,
+    max_orders_per_addr: usize,
+    abort: &AtomicBool,
 ) -> Result<(), ExplorerError>
 where
     I2C: crate::compat::I2cCompat,
     <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
     S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
 {
+    let check_abort = |serial: &mut S| {
+        if abort.load(Ordering::Relaxed) {
+            dvcdbg_log!(serial, "[W] Exploration aborted\r\n");
+            return Err(ExplorerError::Aborted);
+        }
+        Ok(())
+    };
+
     let mut target_addrs = crate::scanner::scan_i2c(i2c, serial, prefix)?;
     if target_addrs.is_empty() {
-        write!(serial, "[I] Init scan OK: No devices found\r\n").ok();
+        dvcdbg_log!(serial, "[I] Init scan OK: No devices found\r\n");
         return Err(ExplorerError::NoValidAddressesFound);
     }
 
@@ -41,74 +164,76 @@ where
 
     loop {
         if target_addrs.is_empty() {
-            write!(serial, "[I] All valid addresses explored. Done.\r\n").ok();
+            dvcdbg_log!(serial, "[I] All valid addresses explored. Done.\r\n");
             return Ok(());
         }
 
         let mut addrs_to_remove = heapless::Vec::<usize, { I2C_MAX_DEVICES }>::new();
 
         for (addr_idx, &addr) in target_addrs.iter().enumerate() {
-            write!(serial, "[I] RUN ON ").ok();
-            crate::compat::util::write_bytes_hex_fmt(serial, &[addr]).ok();
-            write!(serial, "\r\n").ok();
+            check_abort(serial)?;
+            dvcdbg_log!(serial, "[I] RUN ON {addr:02X}\r\n");
 
             let mut failed_nodes = global_failed_nodes;
             let mut sort_iter = match explorer.topological_iter(&failed_nodes) {
                 Ok(iter) => iter,
                 Err(e) => {
-                    write!(serial, "[E] Failed GEN topological sort: {e}\r\n").ok();
+                    dvcdbg_log!(serial, "[E] Failed GEN topological sort: {e}\r\n");
                     addrs_to_remove.push(addr_idx).ok();
                     continue;
                 }
             };
 
-            let mut batched: heapless::Vec<u8, CMD_BUFFER_SIZE> = heapless::Vec::new();
-            batched
-                .push(prefix)
-                .map_err(|_| ExplorerError::BufferOverflow)?;
-
+            let mut first_order = heapless::Vec::<u8, N>::new();
             for cmd_idx in sort_iter.by_ref() {
-                if failed_nodes.get(cmd_idx).unwrap_or(false) {
-                    continue;
-                }
-
-                let cmd_bytes = explorer.nodes[cmd_idx].bytes;
-                if batched.len() + cmd_bytes.len() > CMD_BUFFER_SIZE {
-                    write!(
-                        serial,
-                        "[E] Batch buffer overflow (need {} bytes)\r\n",
-                        batched.len() + cmd_bytes.len()
-                    )
-                    .ok();
-                    return Err(ExplorerError::BufferOverflow);
-                }
-                batched
-                    .extend_from_slice(cmd_bytes)
+                first_order
+                    .push(cmd_idx as u8)
                     .map_err(|_| ExplorerError::BufferOverflow)?;
             }
 
             if sort_iter.is_cycle_detected() {
-                write!(serial, "[E] Dependency cycle detected. Aborting.\r\n").ok();
+                dvcdbg_log!(serial, "[E] Dependency cycle detected. Aborting.\r\n");
+                log_cycle_nodes(serial, &sort_iter.cycle_nodes());
                 return Err(ExplorerError::DependencyCycle);
             }
 
-            match i2c.write(addr, &batched) {
-                Ok(_) => {
-                    write!(
+            let mut ok = write_batch::<_, _, _, N, CMD_BUFFER_SIZE, MAX_DEPS>(
+                explorer,
+                i2c,
+                serial,
+                addr,
+                prefix,
+                &first_order,
+            )?;
+
+            if !ok && max_orders_per_addr > 1 {
+                let alternates = explorer
+                    .all_topological_iter(&failed_nodes)?
+                    .filter(|order| *order != first_order)
+                    .take(max_orders_per_addr - 1);
+                for (attempt, order) in alternates.enumerate() {
+                    check_abort(serial)?;
+                    dvcdbg_log!(
                         serial,
-                        "[I] OK batched @ {addr:02X} ({} bytes)\r\n",
-                        batched.len()
-                    )
-                    .ok();
-                }
-                Err(_) => {
-                    write!(serial, "[W] Failed batched @ {addr:02X}, pruning nodes\r\n").ok();
-                    for cmd_idx in 0..explorer.nodes.len() {
-                        failed_nodes.set(cmd_idx).ok();
+                        "[I] Retry {addr:02X} with order {}/{max_orders_per_addr}\r\n",
+                        attempt + 2
+                    );
+                    ok = write_batch::<_, _, _, N, CMD_BUFFER_SIZE, MAX_DEPS>(
+                        explorer, i2c, serial, addr, prefix, &order,
+                    )?;
+                    if ok {
+                        break;
                     }
                 }
             }
 
+            if !ok {
+                dvcdbg_log!(serial, "[W] Pruning nodes after failures @ {addr:02X}\r\n");
+                for cmd_idx in 0..explorer.nodes().len() {
+                    failed_nodes.set(cmd_idx).ok();
+                }
+            }
+
             global_failed_nodes |= failed_nodes;
 
             addrs_to_remove.push(addr_idx).ok();
@@ -120,39 +245,284 @@ where
     }
 }
 
+/// Writes the commands of `order` to `addr` as a single prefixed batch.
+///
+/// Returns `Ok(false)` when the device rejects the batch.
+fn write_batch<I2C, S, X, const N: usize, const CMD_BUFFER_SIZE: usize, const MAX_DEPS: usize>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    addr: u8,
+    prefix: u8,
+    order: &[u8],
+) -> Result<bool, ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    let batched =
+        assemble_batch::<_, _, N, CMD_BUFFER_SIZE, MAX_DEPS>(explorer, serial, prefix, order)?;
+
+    match i2c.write(addr, &batched) {
+        Ok(_) => {
+            dvcdbg_log!(
+                serial,
+                "[I] OK batched @ {addr:02X} ({} bytes)\r\n",
+                batched.len()
+            );
+            Ok(true)
+        }
+        Err(_) => {
+            dvcdbg_log!(serial, "[W] Failed batched @ {addr:02X}\r\n");
+            Ok(false)
+        }
+    }
+}
+
+/// The prefix followed by the commands of `order`, as one batch.
+fn assemble_batch<S, X, const N: usize, const CMD_BUFFER_SIZE: usize, const MAX_DEPS: usize>(
+    explorer: &X,
+    serial: &mut S,
+    prefix: u8,
+    order: &[u8],
+) -> Result<heapless::Vec<u8, CMD_BUFFER_SIZE>, ExplorerError>
+where
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    let mut batched: heapless::Vec<u8, CMD_BUFFER_SIZE> = heapless::Vec::new();
+    batched
+        .push(prefix)
+        .map_err(|_| ExplorerError::BufferOverflow)?;
+
+    for &cmd_idx in order {
+        let cmd_bytes = explorer.node(cmd_idx as usize)?.bytes;
+        if batched.len() + cmd_bytes.len() > CMD_BUFFER_SIZE {
+            dvcdbg_log!(
+                serial,
+                "[E] Batch buffer overflow (need {} bytes)\r\n",
+                batched.len() + cmd_bytes.len()
+            );
+            return Err(ExplorerError::BufferOverflow);
+        }
+        batched
+            .extend_from_slice(cmd_bytes)
+            .map_err(|_| ExplorerError::BufferOverflow)?;
+    }
+    Ok(batched)
+}
+
+/// Dry run of [`pruning_explorer`]: scans the bus and logs, for each device,
+/// the batch the first attempt would write, without writing anything.
+///
+/// The bus is only probed by the scan, so a `nodes!` table can be checked on
+/// the bench before the real devices are connected. As nothing can fail, no
+/// node is pruned and every address gets the same batch, which is returned.
+///
+/// ```text
+/// [plan] 3C: 00 AE D5 80 AF
+/// ```
+pub fn pruning_plan<
+    I2C,
+    S,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    prefix: u8,
+) -> Result<heapless::Vec<u8, CMD_BUFFER_SIZE>, ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    let target_addrs = crate::scanner::scan_i2c(i2c, serial, prefix)?;
+    if target_addrs.is_empty() {
+        return Err(ExplorerError::NoValidAddressesFound);
+    }
+
+    let failed_nodes = util::BitFlags::new();
+    let mut sort_iter = explorer.topological_iter(&failed_nodes)?;
+    let mut order = heapless::Vec::<u8, N>::new();
+    for cmd_idx in sort_iter.by_ref() {
+        order
+            .push(cmd_idx as u8)
+            .map_err(|_| ExplorerError::BufferOverflow)?;
+    }
+    if sort_iter.is_cycle_detected() {
+        dvcdbg_log!(serial, "[E] Dependency cycle detected. Aborting.\r\n");
+        log_cycle_nodes(serial, &sort_iter.cycle_nodes());
+        return Err(ExplorerError::DependencyCycle);
+    }
+
+    let batched =
+        assemble_batch::<_, _, N, CMD_BUFFER_SIZE, MAX_DEPS>(explorer, serial, prefix, &order)?;
+    for addr in target_addrs.iter() {
+        dvcdbg_log!(serial, "[plan] {addr:02X}: ");
+        dvcdbg_log!(@hex serial, &batched);
+        dvcdbg_log!(serial, "\r\n");
+    }
+    Ok(batched)
+}
+
 #[macro_export]
 macro_rules! get_one_sort {
+    (graph = $graph:ident, $i2c:expr, $serial:expr) => {
+        $crate::explore::runner::one_topological_explorer::<
+            _,
+            _,
+            _,
+            { $graph::N },
+            0,
+            { $graph::CMD_BUFFER_SIZE },
+            { $graph::MAX_DEPS },
+        >(&$graph::EXPLORER, $i2c, $serial, $graph::PREFIX)
+    };
     ($explorer:expr, $i2c:expr, $serial:expr, $prefix:expr, $n:expr, $init_len:expr, $cmd_buf:expr, $max_deps:expr) => {
-        $crate::explore::runner::one_topological_explorer::<_, _, $n, $init_len, $cmd_buf, $max_deps>(
-            $explorer, $i2c, $serial, $prefix,
-        )
+        $crate::explore::runner::one_topological_explorer::<
+            _,
+            _,
+            _,
+            $n,
+            $init_len,
+            $cmd_buf,
+            $max_deps,
+        >($explorer, $i2c, $serial, $prefix)
     };
 }
 
 pub fn one_topological_explorer<
     I2C,
     S,
+    X,
     const N: usize,
     const INIT_SEQUENCE_LEN: usize,
     const CMD_BUFFER_SIZE: usize,
     const MAX_DEPS: usize,
 >(
-    explorer: &Explorer<N, MAX_DEPS>,
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    prefix: u8,
+) -> Result<(), ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    let empty_seq: &[u8] = &[];
+    let mut executor = PrefixExecutor::<INIT_SEQUENCE_LEN, CMD_BUFFER_SIZE>::new(prefix, empty_seq);
+    run_on_bus(explorer, i2c, serial, &mut executor, prefix)
+}
+
+/// Runs one topological sort on the first device found on `i2c`, using a
+/// caller-supplied executor.
+///
+/// This formalizes the multi-bus pattern: the `Explorer` is backed by
+/// `&'static` nodes and can be shared freely between buses, but an executor
+/// remembers which addresses it has already initialized. Pass a fresh
+/// executor per bus, or call [`PrefixExecutor::reset_init_state`] before
+/// reusing one on another bus.
+///
+/// ```ignore
+/// let mut executor = PrefixExecutor::<0, BUF>::new(PREFIX, &[]);
+/// run_on_bus(explorer, &mut i2c0, &mut serial, &mut executor, PREFIX)?;
+/// executor.reset_init_state();
+/// run_on_bus(explorer, &mut i2c1, &mut serial, &mut executor, PREFIX)?;
+/// ```
+pub fn run_on_bus<
+    I2C,
+    S,
+    E,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    executor: &mut E,
+    prefix: u8,
+) -> Result<(), ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+    E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+{
+    run_first_device(explorer, i2c, serial, executor, prefix, None)
+}
+
+/// Same as [`one_topological_explorer`], but times every command with `time`
+/// and logs e.g. `cmd 5: OK (1234 ticks)`, plus the total for the sequence.
+///
+/// Use it to find the init steps that dominate cold-start latency (charge
+/// pumps, oscillator settling, ...).
+pub fn one_topological_explorer_timed<
+    I2C,
+    S,
+    X,
+    T,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    prefix: u8,
+    time: &T,
+) -> Result<(), ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+    T: TimeSource,
+{
+    let mut executor = PrefixExecutor::<0, CMD_BUFFER_SIZE>::new(prefix, &[]);
+    run_first_device(explorer, i2c, serial, &mut executor, prefix, Some(time))
+}
+
+/// Scans, then runs one topological sort on the first device found.
+fn run_first_device<
+    I2C,
+    S,
+    E,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
     i2c: &mut I2C,
     serial: &mut S,
+    executor: &mut E,
     prefix: u8,
+    time: Option<&dyn TimeSource>,
 ) -> Result<(), ExplorerError>
 where
     I2C: crate::compat::I2cCompat,
     <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
     S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+    E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
 {
-    core::fmt::Write::write_str(serial, "[exprore] Attempting to get 1 init seq ...\r\n").ok();
+    dvcdbg_log!(serial, "[exprore] Attempting to get 1 init seq ...\r\n");
 
     let target_addr = match crate::scanner::scan_i2c(i2c, serial, prefix) {
         Ok(addr) => addr,
         Err(e) => {
-            write!(serial, "[error] Failed to scan I2C: {e}\r\n").ok();
+            dvcdbg_log!(serial, "[error] Failed to scan I2C: {e}\r\n");
             return Err(ExplorerError::ExecutionFailed(e));
         }
     };
@@ -161,48 +531,723 @@ where
         return Err(ExplorerError::NoValidAddressesFound);
     }
 
+    execute_one_sort(explorer, i2c, serial, executor, target_addr[0], time)?;
+
+    dvcdbg_log!(
+        serial,
+        "[explorer] Single sequence execution complete for {:02X}.\r\n",
+        target_addr[0]
+    );
+
+    Ok(())
+}
+
+/// Scans, runs one topological sort on the first device found and returns
+/// it as an [`InitProgram`] that can be stored and replayed later.
+///
+/// `delay` times the node delays and the executor pauses, as in
+/// [`InitProgram::replay`].
+pub fn explore_program<
+    'a,
+    I2C,
+    S,
+    D,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &'a X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    prefix: u8,
+    delay: D,
+) -> Result<InitProgram<'a, N, CMD_BUFFER_SIZE>, ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    D: crate::compat::DelayCompat,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    let found = crate::scanner::scan_i2c(i2c, serial, prefix)?;
+    let Some(&addr) = found.first() else {
+        return Err(ExplorerError::NoValidAddressesFound);
+    };
+
+    let program = InitProgram::first_sort(explorer, addr, prefix)?;
+    program.replay(i2c, serial, delay)?;
+    dvcdbg_log!(serial, "[program] Recorded program for {addr:02X}\r\n");
+    Ok(program)
+}
+
+/// Runs a device-specific init graph on each address of a heterogeneous bus.
+///
+/// The bus is scanned once; then each `(addr, explorer)` entry of `plan` gets
+/// one topological sort of its own explorer. All entries share the `N` and
+/// `MAX_DEPS` capacities, so declare smaller graphs with the largest sizes
+/// (`Explorer::<N, MAX_DEPS>::new(&SENSOR_NODES)`). Every entry is attempted;
+/// the addresses that were missing from the scan or failed are returned.
+pub fn run_per_address<
+    I2C,
+    S,
+    E,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    plan: &[(u8, &X)],
+    i2c: &mut I2C,
+    serial: &mut S,
+    executor: &mut E,
+    prefix: u8,
+) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    let found = match crate::scanner::scan_i2c(i2c, serial, prefix) {
+        Ok(addrs) => addrs,
+        Err(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)) => heapless::Vec::new(),
+        Err(e) => return Err(ExplorerError::ExecutionFailed(e)),
+    };
+
+    let mut failed = heapless::Vec::<u8, I2C_MAX_DEVICES>::new();
+    for &(addr, explorer) in plan {
+        let outcome = if found.contains(&addr) {
+            execute_one_sort(explorer, i2c, serial, executor, addr, None)
+        } else {
+            dvcdbg_log!(serial, "[plan] {addr:02X} not found on bus\r\n");
+            Err(ExplorerError::NoValidAddressesFound)
+        };
+        match outcome {
+            Ok(()) => dvcdbg_log!(serial, "[plan] {addr:02X} OK\r\n"),
+            Err(e) => {
+                dvcdbg_log!(serial, "[plan] {addr:02X} FAILED: {e}\r\n");
+                failed
+                    .push(addr)
+                    .map_err(|_| ExplorerError::BufferOverflow)?;
+            }
+        }
+    }
+
+    Ok(failed)
+}
+
+/// Runs one topological sort on every device found on `i2c`, through a
+/// caller-supplied `CmdExecutor` (e.g. a `TracingExecutor`).
+///
+/// This is the multi-device counterpart of [`run_on_bus`]. Unlike
+/// [`pruning_explorer`], commands go through the executor one at a time
+/// instead of as a single raw batch. Every device is attempted; the ones
+/// whose commands fail on the bus are returned (empty when all succeeded).
+pub fn run_on_all_devices<
+    I2C,
+    S,
+    E,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    executor: &mut E,
+    prefix: u8,
+) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    let found = match crate::scanner::scan_i2c(i2c, serial, prefix) {
+        Ok(addrs) if !addrs.is_empty() => addrs,
+        Ok(_) | Err(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)) => {
+            return Err(ExplorerError::NoValidAddressesFound);
+        }
+        Err(e) => return Err(ExplorerError::ExecutionFailed(e)),
+    };
+
+    let mut failed = heapless::Vec::<u8, I2C_MAX_DEVICES>::new();
+    for &addr in found.iter() {
+        match execute_one_sort(explorer, i2c, serial, executor, addr, None) {
+            Ok(()) => dvcdbg_log!(serial, "[all] {addr:02X} OK\r\n"),
+            Err(e @ ExplorerError::ExecutionFailed(_)) => {
+                dvcdbg_log!(serial, "[all] {addr:02X} FAILED: {e}\r\n");
+                failed
+                    .push(addr)
+                    .map_err(|_| ExplorerError::BufferOverflow)?;
+            }
+            // Graph and buffer errors would fail the same way on every address.
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(failed)
+}
+
+/// Sends each frame of `cmds` to `addr` (prefixed, with retry and logging)
+/// and collects one result per frame, without stopping at the first failure.
+///
+/// A building block for when the commands are already known and only the
+/// per-command outcome matters; no scan, graph or ordering is involved. At
+/// most `N` frames are sent; any beyond that are skipped with a warning.
+pub fn run_commands<I2C, S, const N: usize, const CMD_BUFFER_SIZE: usize>(
+    i2c: &mut I2C,
+    serial: &mut S,
+    addr: u8,
+    cmds: &[&[u8]],
+    prefix: u8,
+) -> heapless::Vec<Result<(), crate::error::ExecutorError>, N>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+{
+    let mut executor = PrefixExecutor::<0, CMD_BUFFER_SIZE>::new(prefix, &[]);
+    let mut results = heapless::Vec::new();
+
+    for (idx, cmd) in cmds.iter().enumerate().take(N) {
+        let result = executor.exec(i2c, addr, cmd, serial);
+        match &result {
+            Ok(()) => dvcdbg_log!(serial, "[cmds] {idx}: OK\r\n"),
+            Err(e) => dvcdbg_log!(serial, "[cmds] {idx}: FAIL {e}\r\n"),
+        }
+        // Cannot overflow: at most N iterations.
+        results.push(result).ok();
+    }
+    if cmds.len() > N {
+        dvcdbg_log!(
+            serial,
+            "[cmds] Skipped {} frames beyond capacity {N}\r\n",
+            cmds.len() - N
+        );
+    }
+
+    results
+}
+
+/// Infers "A must precede B" constraints for `cmds` on the device at `addr`
+/// by observing which orders it accepts.
+///
+/// First tries up to `max_orders` permutations (in lexicographic order) until
+/// every command succeeds. Then each command `b` of that working order is
+/// walked towards the front, one earlier command `a` at a time: `b`, together
+/// with the commands already found to precede it, is placed directly in front
+/// of `a` with everything else left in place. If the device now rejects the
+/// sequence, `(a, b)` is recorded, meaning `b` depends on `a`. Pairs implied
+/// by others (`(a, b)` and `(b, c)` imply `(a, c)`) are neither probed nor
+/// returned, so the result maps directly onto `nodes!` deps.
+///
+/// `reset` runs before every attempt and must bring the device back to its
+/// power-on state (toggle a reset pin, send a soft reset, ...); otherwise
+/// state left by an earlier attempt makes later ones succeed regardless of
+/// order. Fails with `NoValidAddressesFound` if no permutation tried worked,
+/// and `BufferOverflow` if more than `MAX_DEPS` constraints remain after the
+/// implied ones are dropped. At most 128 commands are supported.
+pub fn learn_dependencies<
+    I2C,
+    S,
+    F,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    i2c: &mut I2C,
+    serial: &mut S,
+    cmds: &[&[u8]],
+    addr: u8,
+    prefix: u8,
+    max_orders: usize,
+    mut reset: F,
+) -> Result<heapless::Vec<(u8, u8), MAX_DEPS>, ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    F: FnMut(&mut I2C),
+{
+    if cmds.len() > N {
+        return Err(ExplorerError::TooManyCommands);
+    }
+    let mut executor = PrefixExecutor::<0, CMD_BUFFER_SIZE>::new(prefix, &[]);
+    let mut attempt = |i2c: &mut I2C, serial: &mut S, order: &[u8]| {
+        reset(i2c);
+        order
+            .iter()
+            .all(|&idx| executor.exec(i2c, addr, cmds[idx as usize], serial).is_ok())
+    };
+
+    let mut order: heapless::Vec<u8, N> = (0..cmds.len() as u8).collect();
+    let mut tried = 0;
+    loop {
+        if tried == max_orders {
+            dvcdbg_log!(serial, "[learn] No working order in {tried} attempts\r\n");
+            return Err(ExplorerError::NoValidAddressesFound);
+        }
+        tried += 1;
+        if attempt(i2c, serial, &order) {
+            break;
+        }
+        if !next_permutation(&mut order) {
+            dvcdbg_log!(serial, "[learn] No permutation works @ {addr:02X}\r\n");
+            return Err(ExplorerError::NoValidAddressesFound);
+        }
+    }
+    dvcdbg_log!(serial, "[learn] Working order: ");
+    dvcdbg_log!(@hex serial, &order);
+    dvcdbg_log!(serial, "\r\n");
+
+    // `direct[b]` holds the commands whose swap with `b` was rejected;
+    // `before[b]` every command found to precede `b`, directly or not.
+    let mut direct = [util::BitFlags::new(); N];
+    let mut before = [util::BitFlags::new(); N];
+    for j in 1..order.len() {
+        let b = order[j] as usize;
+        // Positions that move together: `b` and the commands it must follow.
+        let mut group = util::BitFlags::new();
+        group.set(j).map_err(ExplorerError::BitFlags)?;
+        for i in (0..j).rev() {
+            let c = order[i] as usize;
+            // A command `b` follows needs `c`, so `b` needs it too; no probe.
+            let implied = group
+                .iter_set()
+                .filter(|&p| p != j)
+                .any(|p| before[order[p] as usize].get(c).unwrap_or(false));
+            if !implied {
+                // Put the group directly in front of `c` and leave everything
+                // else where it was, so a rejection can only be blamed on `c`.
+                let in_group = |p: &usize| group.get(*p).unwrap_or(false);
+                let mut trial: heapless::Vec<u8, N> = heapless::Vec::new();
+                let positions = (0..i)
+                    .chain((i..=j).filter(in_group))
+                    .chain((i..=j).filter(|p| !in_group(p)))
+                    .chain(j + 1..order.len());
+                for p in positions {
+                    // Cannot overflow: a permutation of `order`.
+                    trial.push(order[p]).ok();
+                }
+                if attempt(i2c, serial, &trial) {
+                    continue;
+                }
+                direct[b].set(c).map_err(ExplorerError::BitFlags)?;
+            }
+            group.set(i).map_err(ExplorerError::BitFlags)?;
+            let inherited = before[c];
+            before[b] |= inherited;
+            before[b].set(c).map_err(ExplorerError::BitFlags)?;
+        }
+    }
+
+    let mut deps = heapless::Vec::<(u8, u8), MAX_DEPS>::new();
+    for j in 1..order.len() {
+        let b = order[j];
+        for &a in &order[..j] {
+            if !direct[b as usize].get(a as usize).unwrap_or(false) {
+                continue;
+            }
+            let implied = direct[b as usize]
+                .iter_set()
+                .any(|x| before[x].get(a as usize).unwrap_or(false));
+            if !implied {
+                deps.push((a, b))
+                    .map_err(|_| ExplorerError::BufferOverflow)?;
+            }
+        }
+    }
+    for &(a, b) in deps.iter() {
+        dvcdbg_log!(serial, "[learn] {a} must precede {b}\r\n");
+    }
+    Ok(deps)
+}
+
+/// Logs the nodes a cycle left unplaced, e.g. `cycle involves nodes: 03 05 06`.
+fn log_cycle_nodes<S: core::fmt::Write>(serial: &mut S, stuck: &util::BitFlags) {
+    dvcdbg_log!(serial, "[E] cycle involves nodes:");
+    for idx in stuck.iter_set() {
+        dvcdbg_log!(serial, " {idx:02}");
+    }
+    dvcdbg_log!(serial, "\r\n");
+}
+
+/// Advances `order` to the next lexicographic permutation; `false` after the last.
+fn next_permutation(order: &mut [u8]) -> bool {
+    let Some(pivot) = order.windows(2).rposition(|w| w[0] < w[1]) else {
+        return false;
+    };
+    // A successor exists: `order[pivot + 1]` is larger than the pivot.
+    let Some(succ) = order.iter().rposition(|&x| x > order[pivot]) else {
+        return false;
+    };
+    order.swap(pivot, succ);
+    order[pivot + 1..].reverse();
+    true
+}
+
+/// Event-driven counterpart of [`run_on_bus`]: scans, then runs one
+/// topological sort on the first device found, reporting progress as
+/// [`Event`]s instead of text.
+///
+/// Pass a [`TextSink`](crate::explore::event::TextSink) for the usual text log,
+/// or any other [`EventSink`] to render to defmt, a binary protocol, etc.
+/// Text written by the executor arrives as [`Event::Log`].
+pub fn run_with_events<
+    I2C,
+    E,
+    K,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    executor: &mut E,
+    sink: &mut K,
+    prefix: u8,
+) -> Result<(), ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+    K: EventSink,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    sink.on_event(Event::Scanning { ctrl_byte: prefix });
+    let found = crate::scanner::internal_scan(i2c).map_err(ExplorerError::ExecutionFailed)?;
+    for &addr in found.iter() {
+        sink.on_event(Event::Found(addr));
+    }
+    let Some(&addr) = found.first() else {
+        return Err(ExplorerError::NoValidAddressesFound);
+    };
+
+    let failed_nodes = util::BitFlags::new();
+    let mut sort_iter = explorer.topological_iter(&failed_nodes)?;
+    for idx in sort_iter.by_ref() {
+        let mut writer = EventWriter { sink: &mut *sink };
+        let node = explorer.node(idx)?;
+        if let Err(err) = executor.exec(i2c, addr, node.bytes, &mut writer) {
+            sink.on_event(Event::CmdFail {
+                addr,
+                idx,
+                err: &err,
+            });
+            return Err(ExplorerError::from(err).with_command_index(idx));
+        }
+        if node.delay_us > 0 {
+            executor.pause_us(node.delay_us);
+        }
+        sink.on_event(Event::CmdOk { addr, idx });
+    }
+    if sort_iter.is_cycle_detected() {
+        sink.on_event(Event::Cycle);
+        return Err(ExplorerError::DependencyCycle);
+    }
+
+    sink.on_event(Event::Done(addr));
+    Ok(())
+}
+
+/// Reruns one topological sort only on `failed_addrs`, without scanning the bus.
+///
+/// Devices that already initialized are left untouched, which suits the
+/// fix-wiring-and-retry loop. Every address is attempted; the ones whose
+/// commands fail again on the bus are returned (empty when all succeeded).
+pub fn rescan_failed<
+    I2C,
+    S,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    failed_addrs: &[u8],
+    prefix: u8,
+) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    let mut executor = PrefixExecutor::<0, CMD_BUFFER_SIZE>::new(prefix, &[]);
+    let mut still_failed = heapless::Vec::<u8, I2C_MAX_DEVICES>::new();
+
+    for &addr in failed_addrs {
+        dvcdbg_log!(serial, "[rescan] Retrying {addr:02X}\r\n");
+        match execute_one_sort(explorer, i2c, serial, &mut executor, addr, None) {
+            Ok(()) => dvcdbg_log!(serial, "[rescan] {addr:02X} OK\r\n"),
+            Err(e @ ExplorerError::ExecutionFailed(_)) => {
+                dvcdbg_log!(serial, "[rescan] {addr:02X} still failing: {e}\r\n");
+                still_failed
+                    .push(addr)
+                    .map_err(|_| ExplorerError::BufferOverflow)?;
+            }
+            // Graph and buffer errors would fail the same way on every address.
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(still_failed)
+}
+
+/// Executes one topological sort of `explorer` on `addr`, stopping at the first
+/// failed command. With a `time` source, each command is timed and logged.
+fn execute_one_sort<
+    I2C,
+    S,
+    E,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    executor: &mut E,
+    addr: u8,
+    time: Option<&dyn TimeSource>,
+) -> Result<(), ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+    E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+{
     let failed_nodes = util::BitFlags::new();
     let mut sort_iter = match explorer.topological_iter(&failed_nodes) {
         Ok(iter) => iter,
         Err(e) => {
-            write!(
+            dvcdbg_log!(
                 serial,
                 "[E] Failed to GEN topological sort: {e}. Aborting.\r\n"
-            )
-            .ok();
+            );
             return Err(e);
         }
     };
 
-    core::fmt::Write::write_str(
+    dvcdbg_log!(
         serial,
-        "[explorer] Obtained one topological sort. Executing on ",
-    )
-    .ok();
-    crate::compat::util::write_bytes_hex_fmt(serial, &[target_addr[0]]).ok();
-    core::fmt::Write::write_str(serial, "...\r\n").ok();
-
-    let empty_seq: &[u8] = &[];
-    let mut executor = PrefixExecutor::<INIT_SEQUENCE_LEN, CMD_BUFFER_SIZE>::new(prefix, empty_seq);
+        "[explorer] Obtained one topological sort. Executing on {addr:02X}...\r\n"
+    );
 
+    let sort_start = time.map(|t| t.now());
     for cmd_idx in sort_iter.by_ref() {
-        super::explorer::exec_log_cmd(
-            i2c,
-            &mut executor,
-            serial,
-            target_addr[0],
-            explorer.nodes[cmd_idx].bytes,
-            cmd_idx,
-        )?;
+        let start = time.map(|t| t.now());
+        let node = explorer.node(cmd_idx)?;
+        let res = super::explorer::exec_log_cmd(i2c, executor, serial, addr, node.bytes, cmd_idx);
+        if let (Some(t), Some(start)) = (time, start) {
+            let elapsed = t.now().wrapping_sub(start);
+            let status = if res.is_ok() { "OK" } else { "FAIL" };
+            dvcdbg_log!(serial, "cmd {cmd_idx}: {status} ({elapsed} ticks)\r\n");
+        }
+        res?;
+        if node.delay_us > 0 {
+            executor.pause_us(node.delay_us);
+        }
+    }
+    if let (Some(t), Some(start)) = (time, sort_start) {
+        let total = t.now().wrapping_sub(start);
+        dvcdbg_log!(serial, "[explorer] Sequence took {total} ticks\r\n");
     }
     if sort_iter.is_cycle_detected() {
-        core::fmt::Write::write_str(serial, "[error] Dependency cycle detected!\r\n").ok();
+        dvcdbg_log!(serial, "[error] Dependency cycle detected!\r\n");
+        log_cycle_nodes(serial, &sort_iter.cycle_nodes());
         return Err(ExplorerError::DependencyCycle);
     }
 
-    core::fmt::Write::write_str(serial, "[explorer] Single sequence execution complete for ").ok();
-    crate::compat::util::write_bytes_hex_fmt(serial, &[target_addr[0]]).ok();
-    core::fmt::Write::write_str(serial, ".\r\n").ok();
+    Ok(())
+}
+
+/// Checks that running the init sequence twice leaves the device in the same state.
+///
+/// Executes one topological sort on `addr`, reads `STATUS_LEN` bytes from
+/// `verify_reg`, executes the sort again and reads the register a second time.
+/// Returns `Ok(true)` when both snapshots match. A mismatch points at
+/// order-dependent or stateful commands (toggles, counters, one-shot modes)
+/// that make the sequence fragile.
+pub fn check_idempotent<
+    I2C,
+    S,
+    E,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+    const STATUS_LEN: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    executor: &mut E,
+    addr: u8,
+    verify_reg: u8,
+) -> Result<bool, ExplorerError>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    X: NodeSet<N, MAX_DEPS>,
+    E: CmdExecutor<I2C, CMD_BUFFER_SIZE>,
+{
+    let mut snapshots = [[0u8; STATUS_LEN]; 2];
+
+    for (pass, snapshot) in snapshots.iter_mut().enumerate() {
+        dvcdbg_log!(serial, "[idem] Pass {} @ {addr:02X}\r\n", pass + 1);
+        execute_one_sort(explorer, i2c, serial, executor, addr, None)?;
+        i2c.write_read(addr, &[verify_reg], snapshot)
+            .map_err(|e| ExplorerError::ExecutionFailed(e.to_compat(Some(addr))))?;
+        dvcdbg_log!(serial, "[idem] Status {verify_reg:02X}: ");
+        dvcdbg_log!(@hex serial, snapshot);
+        dvcdbg_log!(serial, "\r\n");
+    }
+
+    let idempotent = snapshots[0] == snapshots[1];
+    if idempotent {
+        dvcdbg_log!(serial, "[idem] OK: sequence is idempotent\r\n");
+    } else {
+        dvcdbg_log!(
+            serial,
+            "[idem] MISMATCH: state differs after second run\r\n"
+        );
+    }
+    Ok(idempotent)
+}
+
+/// Async counterpart of [`one_topological_explorer`] for `I2cCompatAsync` buses.
+///
+/// Scans, then writes one topological sort to the first device found, each
+/// command prefixed with `prefix`. Sorting is plain computation and stays
+/// synchronous; only the bus accesses and pauses are awaited.
+///
+/// Writes follow the default policy of a [`PrefixExecutor`] with a HAL
+/// delay: two attempts per command, 1000 µs after a failed and after a
+/// successful write, and the same `I2C WRITE` and retry log lines. `delay`
+/// also awaits the [`CmdNode::delay_us`] pause after each command. Unlike
+/// `PrefixExecutor` the policy is fixed, and there is no init sequence,
+/// presence probe or chunking of oversized commands.
+#[cfg(feature = "ehal_async")]
+pub async fn one_topological_explorer_async<
+    I2C,
+    S,
+    D,
+    X,
+    const N: usize,
+    const CMD_BUFFER_SIZE: usize,
+    const MAX_DEPS: usize,
+>(
+    explorer: &X,
+    i2c: &mut I2C,
+    serial: &mut S,
+    delay: &mut D,
+    prefix: u8,
+) -> Result<(), ExplorerError>
+where
+    I2C: crate::compat::I2cCompatAsync,
+    <I2C as crate::compat::I2cCompatAsync>::Error: crate::compat::HalErrorExt,
+    S: core::fmt::Write,
+    D: embedded_hal_async::delay::DelayNs,
+    X: NodeSet<N, MAX_DEPS>,
+{
+    dvcdbg_log!(serial, "[exprore] Attempting to get 1 init seq ...\r\n");
+    dvcdbg_log!(serial, "Scanning I2C bus with a {prefix:02X} ...\r\n");
+
+    let target_addr = match crate::scanner::internal_scan_async(i2c).await {
+        Ok(addrs) => addrs,
+        Err(e) => {
+            dvcdbg_log!(serial, "[error] Failed to scan I2C: {e}\r\n");
+            return Err(ExplorerError::ExecutionFailed(e));
+        }
+    };
+    dvcdbg_log!(serial, "Found device @ ");
+    dvcdbg_log!(@hex serial, &target_addr);
+    dvcdbg_log!(serial, "\r\n");
+    let Some(&addr) = target_addr.first() else {
+        return Err(ExplorerError::NoValidAddressesFound);
+    };
 
+    let failed_nodes = util::BitFlags::new();
+    let mut sort_iter = match explorer.topological_iter(&failed_nodes) {
+        Ok(iter) => iter,
+        Err(e) => {
+            dvcdbg_log!(
+                serial,
+                "[E] Failed to GEN topological sort: {e}. Aborting.\r\n"
+            );
+            return Err(e);
+        }
+    };
+
+    dvcdbg_log!(
+        serial,
+        "[explorer] Obtained one topological sort. Executing on {addr:02X}...\r\n"
+    );
+
+    let mut buffer = heapless::Vec::<u8, CMD_BUFFER_SIZE>::new();
+    for cmd_idx in sort_iter.by_ref() {
+        let node = explorer.node(cmd_idx)?;
+        let cmd = node.bytes;
+        buffer.clear();
+        if buffer.push(prefix).is_err() || buffer.extend_from_slice(cmd).is_err() {
+            let err = ExplorerError::CommandTooLarge {
+                index: Some(cmd_idx),
+                required: 1 + cmd.len(),
+                available: CMD_BUFFER_SIZE,
+            };
+            dvcdbg_log!(serial, "[E] FAIL {err}\r\n");
+            return Err(err);
+        }
+
+        let mut result = Ok(());
+        for _attempt in 0..DEFAULT_MAX_ATTEMPTS {
+            log_write(serial, addr, &buffer);
+            result = i2c.write(addr, &buffer).await;
+            match &result {
+                Ok(()) => {
+                    delay.delay_us(DEFAULT_WRITE_DELAY).await;
+                    break;
+                }
+                Err(e) => {
+                    let err = e.to_compat_at(addr);
+                    dvcdbg_log!(@ascii serial, "[I2C retry error] {err}");
+                    delay.delay_us(DEFAULT_WRITE_DELAY).await;
+                }
+            }
+        }
+        match result {
+            Ok(()) => dvcdbg_log!(serial, "[E] OK {cmd_idx}\r\n"),
+            Err(e) => {
+                let err = e.to_compat_at(addr);
+                dvcdbg_log!(serial, "[E] FAIL {cmd_idx}: {err}\r\n");
+                return Err(ExplorerError::ExecutionFailed(err.kind));
+            }
+        }
+        if node.delay_us > 0 {
+            delay.delay_us(node.delay_us).await;
+        }
+    }
+    if sort_iter.is_cycle_detected() {
+        dvcdbg_log!(serial, "[error] Dependency cycle detected!\r\n");
+        log_cycle_nodes(serial, &sort_iter.cycle_nodes());
+        return Err(ExplorerError::DependencyCycle);
+    }
+
+    dvcdbg_log!(
+        serial,
+        "[explorer] Single sequence execution complete for {addr:02X}.\r\n"
+    );
     Ok(())
 }
@@ -0,0 +1,176 @@
+// trace.rs
+
+use crate::compat::HalErrorExt;
+use crate::compat::I2cCompat;
+use crate::error::{ExecutorError, ExplorerError};
+use crate::explore::explorer::CmdExecutor;
+
+/// One I2C write captured by a [`TracingExecutor`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceRecord<const MAX_BYTES: usize> {
+    /// Target address of the write.
+    pub addr: u8,
+    /// Exact bytes that went over the bus (prefix and init frames included).
+    pub bytes: heapless::Vec<u8, MAX_BYTES>,
+    /// Whether the device acknowledged the write.
+    pub ok: bool,
+}
+
+/// A command executor that records every bus write issued by an inner executor.
+///
+/// The inner executor talks to the bus through a recording tap, so the trace
+/// holds the frames exactly as written, including prefixes, init sequences
+/// and retries. Writes longer than `MAX_BYTES`, or arriving after `CAP`
+/// records, are not stored and are counted in [`TracingExecutor::dropped`].
+pub struct TracingExecutor<E, const CAP: usize, const MAX_BYTES: usize> {
+    inner: E,
+    trace: heapless::Vec<TraceRecord<MAX_BYTES>, CAP>,
+    dropped: usize,
+}
+
+impl<E, const CAP: usize, const MAX_BYTES: usize> TracingExecutor<E, CAP, MAX_BYTES> {
+    pub fn new(inner: E) -> Self {
+        Self {
+            inner,
+            trace: heapless::Vec::new(),
+            dropped: 0,
+        }
+    }
+
+    /// The writes captured so far, in bus order.
+    pub fn trace(&self) -> &[TraceRecord<MAX_BYTES>] {
+        &self.trace
+    }
+
+    /// Number of writes that could not be stored.
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    /// Discard the captured trace.
+    pub fn clear(&mut self) {
+        self.trace.clear();
+        self.dropped = 0;
+    }
+
+    /// Consume the tracer, returning the wrapped executor.
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+/// I2C wrapper handed to the inner executor; records writes into the trace.
+pub struct TraceTap<'a, I2C, const CAP: usize, const MAX_BYTES: usize> {
+    i2c: &'a mut I2C,
+    trace: &'a mut heapless::Vec<TraceRecord<MAX_BYTES>, CAP>,
+    dropped: &'a mut usize,
+}
+
+impl<I2C, const CAP: usize, const MAX_BYTES: usize> I2cCompat for TraceTap<'_, I2C, CAP, MAX_BYTES>
+where
+    I2C: I2cCompat,
+{
+    type Error = I2C::Error;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        let res = self.i2c.write(addr, bytes);
+        let record = heapless::Vec::from_slice(bytes).map(|bytes| TraceRecord {
+            addr,
+            bytes,
+            ok: res.is_ok(),
+        });
+        let stored = match record {
+            Ok(record) => self.trace.push(record).is_ok(),
+            Err(_) => false,
+        };
+        if !stored {
+            *self.dropped += 1;
+        }
+        res
+    }
+
+    fn read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.read(addr, buffer)
+    }
+
+    fn write_read(&mut self, addr: u8, bytes: &[u8], buffer: &mut [u8]) -> Result<(), Self::Error> {
+        self.i2c.write_read(addr, bytes, buffer)
+    }
+
+    fn probe(&mut self, addr: u8) -> Result<bool, Self::Error> {
+        self.i2c.probe(addr)
+    }
+
+    fn is_nack(&self, error: &Self::Error) -> bool {
+        self.i2c.is_nack(error)
+    }
+}
+
+impl<I2C, E, const CMD_BUFFER_SIZE: usize, const CAP: usize, const MAX_BYTES: usize>
+    CmdExecutor<I2C, CMD_BUFFER_SIZE> for TracingExecutor<E, CAP, MAX_BYTES>
+where
+    I2C: I2cCompat,
+    E: for<'a> CmdExecutor<TraceTap<'a, I2C, CAP, MAX_BYTES>, CMD_BUFFER_SIZE>,
+{
+    fn exec<W: core::fmt::Write>(
+        &mut self,
+        i2c: &mut I2C,
+        addr: u8,
+        cmd: &[u8],
+        writer: &mut W,
+    ) -> Result<(), ExecutorError> {
+        let mut tap = TraceTap {
+            i2c,
+            trace: &mut self.trace,
+            dropped: &mut self.dropped,
+        };
+        self.inner.exec(&mut tap, addr, cmd, writer)
+    }
+
+    fn pause_us(&mut self, us: u32) {
+        <E as CmdExecutor<TraceTap<'_, I2C, CAP, MAX_BYTES>, CMD_BUFFER_SIZE>>::pause_us(
+            &mut self.inner,
+            us,
+        );
+    }
+}
+
+/// Re-sends the successful writes of a captured trace to `addr`, in order.
+///
+/// Failed records (e.g. attempts that were later retried) are skipped, so a
+/// trace of a good run replays as the clean sequence that initialized the
+/// device. Stops at the first write that fails and returns the number of
+/// writes replayed otherwise.
+pub fn replay_trace<I2C, S, const MAX_BYTES: usize>(
+    i2c: &mut I2C,
+    serial: &mut S,
+    trace: &[TraceRecord<MAX_BYTES>],
+    addr: u8,
+) -> Result<usize, ExplorerError>
+where
+    I2C: I2cCompat,
+    <I2C as I2cCompat>::Error: HalErrorExt,
+    S: core::fmt::Write,
+{
+    dvcdbg_log!(serial, "[replay] {} records to {addr:02X}\r\n", trace.len());
+
+    let mut replayed = 0;
+    for (idx, record) in trace.iter().enumerate().filter(|(_, r)| r.ok) {
+        dvcdbg_log!(serial, "[replay] {idx}: ");
+        dvcdbg_log!(@hex serial, &record.bytes);
+        match i2c.write(addr, &record.bytes) {
+            Ok(_) => {
+                dvcdbg_log!(serial, " OK\r\n");
+                replayed += 1;
+            }
+            Err(e) => {
+                let err = e.to_compat_at(addr);
+                dvcdbg_log!(serial, " FAIL: {err}\r\n");
+                return Err(ExplorerError::ExecutionFailed(err.kind));
+            }
+        }
+    }
+
+    dvcdbg_log!(serial, "[replay] Done, {replayed} writes\r\n");
+    Ok(replayed)
+}
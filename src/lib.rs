@@ -5,12 +5,12 @@
 //! Lightweight diagnostic utilities for embedded Rust.
 //! Compatible with `no_std` and multiple HAL backends.
 
-pub mod scanner;
-
 #[macro_use]
 pub mod macros;
 
 pub mod compat;
 pub mod error;
 pub mod explore;
+pub mod logger;
 pub mod prelude;
+pub mod scanner;
@@ -0,0 +1,553 @@
+//! src/logger.rs
+//! Writers that decorate diagnostic output before it reaches the serial sink.
+//!
+//! None of them owns a text buffer: each borrows its sink and formats straight
+//! through it, so stacking or nesting loggers costs a few words of stack
+//! rather than one buffer per logger. The exception is [`RingLogger`], whose
+//! buffer is the point: it keeps recent output in RAM instead of sending it.
+
+use crate::compat::TimeSource;
+use core::fmt;
+
+/// A `core::fmt::Write` wrapper that tags every log line with a run identifier.
+///
+/// Wrap the serial writer with a fresh ID before each runner invocation so the
+/// UART log of one run can be matched with an external capture (e.g. a logic
+/// analyzer session). The ID can be any `u16`, such as a counter or the low
+/// bits of a timer (`timer.now() as u16`).
+///
+/// # Example
+///
+/// ```ignore
+/// let mut tagged = RunIdWriter::new(&mut serial, 0x1A3F);
+/// get_one_sort!(explorer, &mut i2c, &mut tagged, PREFIX, N, 0, BUF, MAX_DEPS)?;
+/// // [run 1A3F] [exprore] Attempting to get 1 init seq ...
+/// ```
+pub struct RunIdWriter<'a, W: fmt::Write> {
+    inner: &'a mut W,
+    run_id: u16,
+    at_line_start: bool,
+}
+
+impl<'a, W: fmt::Write> RunIdWriter<'a, W> {
+    /// Create a writer that prefixes each line with `[run XXXX] `.
+    pub fn new(inner: &'a mut W, run_id: u16) -> Self {
+        Self {
+            inner,
+            run_id,
+            at_line_start: true,
+        }
+    }
+
+    /// The identifier printed in front of each line.
+    pub fn run_id(&self) -> u16 {
+        self.run_id
+    }
+}
+
+impl<W: fmt::Write> fmt::Write for RunIdWriter<'_, W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for line in s.split_inclusive('\n') {
+            if self.at_line_start {
+                write!(self.inner, "[run {:04X}] ", self.run_id)?;
+            }
+            self.inner.write_str(line)?;
+            self.at_line_start = line.ends_with('\n');
+        }
+        Ok(())
+    }
+}
+
+/// A `core::fmt::Write` wrapper that stamps every log line with the current
+/// tick of a [`TimeSource`].
+///
+/// The time is read when a line starts, so the gap between two commands shows
+/// directly in the log. Ticks are in the unit of the time source (cycles,
+/// microseconds...) and printed raw, so a wrapping counter simply restarts
+/// from zero. [`TimestampedLogger::padded`] switches to fixed-width
+/// `[00012345] ` stamps that line up in a terminal.
+///
+/// ```ignore
+/// let mut stamped = TimestampedLogger::new(&mut serial, &timer);
+/// get_one_sort!(explorer, &mut i2c, &mut stamped, PREFIX, N, 0, BUF, MAX_DEPS)?;
+/// // [t=12345] [E] OK 4
+/// // [t=212345] [E] OK 5
+/// ```
+pub struct TimestampedLogger<'a, S: fmt::Write, T: TimeSource> {
+    inner: &'a mut S,
+    time: &'a T,
+    padded: bool,
+    at_line_start: bool,
+}
+
+impl<'a, S: fmt::Write, T: TimeSource> TimestampedLogger<'a, S, T> {
+    /// Create a writer that prefixes each line with `[t=<now>] `.
+    pub fn new(inner: &'a mut S, time: &'a T) -> Self {
+        Self {
+            inner,
+            time,
+            padded: false,
+            at_line_start: true,
+        }
+    }
+
+    /// Stamp lines as `[00012345] `, the tick zero-padded to 8 digits
+    /// (ticks above 99 999 999 print in full).
+    pub fn padded(mut self) -> Self {
+        self.padded = true;
+        self
+    }
+}
+
+impl<S: fmt::Write, T: TimeSource> fmt::Write for TimestampedLogger<'_, S, T> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for line in s.split_inclusive('\n') {
+            if self.at_line_start {
+                let now = self.time.now();
+                if self.padded {
+                    write!(self.inner, "[{now:08}] ")?;
+                } else {
+                    write!(self.inner, "[t={now}] ")?;
+                }
+            }
+            self.inner.write_str(line)?;
+            self.at_line_start = line.ends_with('\n');
+        }
+        Ok(())
+    }
+}
+
+/// Leveled logging on top of any `core::fmt::Write`.
+///
+/// The crate's runners take a plain `core::fmt::Write` for their logs, so
+/// there is no logger type to wrap a UART in. This trait adds `[Info]`,
+/// `[Warn]` and `[Error]` prefixed lines for application code and is
+/// implemented for every writer. Like all crate output, it compiles to
+/// nothing with the `no-log` feature.
+///
+/// ```ignore
+/// serial.info(format_args!("found {} devices", found.len()));
+/// // [Info] found 2 devices
+/// ```
+pub trait Logger: fmt::Write {
+    fn info(&mut self, args: fmt::Arguments<'_>) {
+        dvcdbg_log!(self, "[Info] {args}\r\n");
+    }
+
+    fn warn(&mut self, args: fmt::Arguments<'_>) {
+        dvcdbg_log!(self, "[Warn] {args}\r\n");
+    }
+
+    fn error(&mut self, args: fmt::Arguments<'_>) {
+        dvcdbg_log!(self, "[Error] {args}\r\n");
+    }
+
+    /// Hex dump of `data`, 16 bytes per line, each line starting with its
+    /// offset from `addr_base` and ending with the printable ASCII (`.` for
+    /// the rest):
+    ///
+    /// ```text
+    /// 0010: 48 65 6C 6C 6F 00 FF                             |Hello..|
+    /// ```
+    fn log_hex_dump(&mut self, addr_base: u16, data: &[u8]) {
+        if cfg!(feature = "no-log") {
+            return;
+        }
+        for (i, line) in data.chunks(16).enumerate() {
+            let offset = addr_base.wrapping_add((i * 16) as u16);
+            write!(self, "{offset:04X}: ").ok();
+            crate::compat::util::write_bytes_hex_fmt(self, line).ok();
+            // Pad short lines so the gutters line up: 3 columns per missing byte.
+            write!(self, "{:1$}|", "", 2 + (16 - line.len()) * 3).ok();
+            for &b in line {
+                let c = if b.is_ascii_graphic() || b == b' ' {
+                    b as char
+                } else {
+                    '.'
+                };
+                self.write_char(c).ok();
+            }
+            self.write_str("|\r\n").ok();
+        }
+    }
+}
+
+impl<W: fmt::Write + ?Sized> Logger for W {}
+
+/// A [`Logger`] wrapper that tallies messages per level before forwarding
+/// them to the inner logger.
+///
+/// Every [`Logger`] call writes its line through `core::fmt::Write`, so the
+/// wrapper counts the lines that start with an `[Info] `, `[Warn] ` or
+/// `[Error] ` tag, which also covers code that only sees a generic
+/// `impl Logger`. The inherent `info`/`warn`/`error` count before logging, so
+/// called directly on a `CountingLogger` they keep counting with the `no-log`
+/// feature, where no line is written; through `impl Logger` nothing is
+/// counted in such builds.
+///
+/// ```ignore
+/// let mut log = CountingLogger::new(&mut serial);
+/// log.warn(format_args!("retrying {addr:02X}"));
+/// let (_, warns, errors) = log.counts();
+/// serial.info(format_args!("during init: {warns} warnings, {errors} errors"));
+/// ```
+pub struct CountingLogger<'a, L: Logger> {
+    inner: &'a mut L,
+    info: u32,
+    warn: u32,
+    error: u32,
+    at_line_start: bool,
+}
+
+impl<'a, L: Logger> CountingLogger<'a, L> {
+    pub fn new(inner: &'a mut L) -> Self {
+        Self {
+            inner,
+            info: 0,
+            warn: 0,
+            error: 0,
+            at_line_start: true,
+        }
+    }
+
+    /// Messages logged so far, as `(info, warn, error)`.
+    pub fn counts(&self) -> (u32, u32, u32) {
+        (self.info, self.warn, self.error)
+    }
+
+    pub fn info(&mut self, args: fmt::Arguments<'_>) {
+        self.info = self.info.saturating_add(1);
+        self.inner.info(args);
+    }
+
+    pub fn warn(&mut self, args: fmt::Arguments<'_>) {
+        self.warn = self.warn.saturating_add(1);
+        self.inner.warn(args);
+    }
+
+    pub fn error(&mut self, args: fmt::Arguments<'_>) {
+        self.error = self.error.saturating_add(1);
+        self.inner.error(args);
+    }
+}
+
+impl<L: Logger> fmt::Write for CountingLogger<'_, L> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        for line in s.split_inclusive('\n') {
+            if self.at_line_start {
+                let counter = if line.starts_with("[Info] ") {
+                    Some(&mut self.info)
+                } else if line.starts_with("[Warn] ") {
+                    Some(&mut self.warn)
+                } else if line.starts_with("[Error] ") {
+                    Some(&mut self.error)
+                } else {
+                    None
+                };
+                if let Some(count) = counter {
+                    *count = count.saturating_add(1);
+                }
+            }
+            self.inner.write_str(line)?;
+            self.at_line_start = line.ends_with('\n');
+        }
+        Ok(())
+    }
+}
+
+/// A `core::fmt::Write` that sends everything to two writers, e.g. a UART
+/// and a [`RingLogger`].
+///
+/// Each write goes to `first`, then to `second`, and fails if either fails
+/// (`second` is not written when `first` fails). Writes are sequential, so a
+/// slow second sink throttles the first: every log line waits for both.
+///
+/// ```ignore
+/// let mut recorder = RingLogger::<512>::new();
+/// let mut tee = TeeWriter::new(&mut serial, &mut recorder);
+/// get_one_sort!(explorer, &mut i2c, &mut tee, PREFIX, N, 0, BUF, MAX_DEPS)?;
+/// ```
+pub struct TeeWriter<'a, A: fmt::Write, B: fmt::Write> {
+    first: &'a mut A,
+    second: &'a mut B,
+}
+
+impl<'a, A: fmt::Write, B: fmt::Write> TeeWriter<'a, A, B> {
+    pub fn new(first: &'a mut A, second: &'a mut B) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<A: fmt::Write, B: fmt::Write> fmt::Write for TeeWriter<'_, A, B> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.first.write_str(s)?;
+        self.second.write_str(s)
+    }
+}
+
+/// A [`Logger`] that keeps the last `CAP` bytes of output in RAM.
+///
+/// Writes never fail: once the buffer is full, the oldest bytes are
+/// overwritten. Nothing is sent anywhere until [`RingLogger::dump`], so the
+/// recent history survives to be flushed from a panic handler or on a
+/// debugger request, like a flight recorder.
+///
+/// ```ignore
+/// let mut recorder = RingLogger::<1024>::new();
+/// if run_on_bus(explorer, &mut i2c, &mut recorder, &mut executor, PREFIX).is_err() {
+///     recorder.dump(&mut serial).ok();
+/// }
+/// ```
+pub struct RingLogger<const CAP: usize> {
+    buf: [u8; CAP],
+    /// Index of the next byte to write.
+    head: usize,
+    len: usize,
+}
+
+impl<const CAP: usize> Default for RingLogger<CAP> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const CAP: usize> RingLogger<CAP> {
+    pub const fn new() -> Self {
+        Self {
+            buf: [0; CAP],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    /// Number of bytes retained, at most `CAP`.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Discard the retained output.
+    pub fn clear(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    /// Writes the retained output to `w`, oldest first, leaving it in place.
+    ///
+    /// A character cut by the overwrite (or split at the wrap point) is
+    /// written as `?`.
+    pub fn dump<W: fmt::Write>(&self, w: &mut W) -> fmt::Result {
+        let start = (self.head + CAP - self.len) % CAP.max(1);
+        let (older, newer) = if start + self.len <= CAP {
+            (&self.buf[start..start + self.len], &[][..])
+        } else {
+            (&self.buf[start..], &self.buf[..self.head])
+        };
+        for part in [older, newer] {
+            for chunk in part.utf8_chunks() {
+                w.write_str(chunk.valid())?;
+                if !chunk.invalid().is_empty() {
+                    w.write_char('?')?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<const CAP: usize> fmt::Write for RingLogger<CAP> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if CAP == 0 {
+            return Ok(());
+        }
+        for &b in s.as_bytes() {
+            self.buf[self.head] = b;
+            self.head = (self.head + 1) % CAP;
+        }
+        self.len = (self.len + s.len()).min(CAP);
+        Ok(())
+    }
+}
+
+/// A `core::fmt::Write` sink that captures output into a fixed-size string.
+///
+/// Meant for tests that assert on log output. Writes beyond `N` bytes fail
+/// with `fmt::Error`, like any other full writer.
+///
+/// ```ignore
+/// let mut writer = StringWriter::<256>::new();
+/// scan_i2c(&mut i2c, &mut writer, 0x00)?;
+/// assert!(writer.as_str().contains("Found device"));
+/// ```
+#[cfg(any(test, feature = "testing"))]
+#[derive(Default)]
+pub struct StringWriter<const N: usize> {
+    buf: heapless::String<N>,
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl<const N: usize> StringWriter<N> {
+    pub fn new() -> Self {
+        Self {
+            buf: heapless::String::new(),
+        }
+    }
+
+    /// Everything written so far.
+    pub fn as_str(&self) -> &str {
+        self.buf.as_str()
+    }
+
+    /// Discard the captured output.
+    pub fn clear(&mut self) {
+        self.buf.clear();
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl<const N: usize> fmt::Write for StringWriter<N> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.buf.push_str(s).map_err(|_| fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::fmt::Write;
+
+    #[test]
+    fn test_run_id_prefixes_each_line() {
+        let mut out = StringWriter::<128>::new();
+        {
+            let mut w = RunIdWriter::new(&mut out, 0x1A3F);
+            write!(w, "first\r\nsec").unwrap();
+            write!(w, "ond\r\n").unwrap();
+        }
+        assert_eq!(out.as_str(), "[run 1A3F] first\r\n[run 1A3F] second\r\n");
+    }
+
+    #[test]
+    fn test_timestamped_logger_stamps_each_line() {
+        use core::cell::Cell;
+
+        let ticks = Cell::new(0u32);
+        let time = || {
+            ticks.set(ticks.get() + 100);
+            ticks.get()
+        };
+        let mut out = StringWriter::<64>::new();
+        {
+            let mut w = TimestampedLogger::new(&mut out, &time);
+            write!(w, "a\r\nb").unwrap();
+            write!(w, "c\r\n").unwrap();
+        }
+        assert_eq!(out.as_str(), "[t=100] a\r\n[t=200] bc\r\n");
+    }
+
+    #[test]
+    fn test_timestamped_logger_padded_wraps_raw() {
+        let ticks = core::cell::Cell::new(u32::MAX);
+        let time = || {
+            let now = ticks.get();
+            ticks.set(now.wrapping_add(12_346));
+            now
+        };
+        let mut out = StringWriter::<64>::new();
+        {
+            let mut w = TimestampedLogger::new(&mut out, &time).padded();
+            write!(w, "a\r\nb\r\n").unwrap();
+        }
+        assert_eq!(out.as_str(), "[4294967295] a\r\n[00012345] b\r\n");
+    }
+
+    #[test]
+    fn test_counting_logger_tallies_levels() {
+        let mut out = StringWriter::<128>::new();
+        let mut log = CountingLogger::new(&mut out);
+        log.warn(format_args!("retry"));
+        log.warn(format_args!("retry"));
+        log.error(format_args!("nack"));
+        assert_eq!(log.counts(), (0, 2, 1));
+        #[cfg(not(feature = "no-log"))]
+        assert_eq!(
+            out.as_str(),
+            "[Warn] retry\r\n[Warn] retry\r\n[Error] nack\r\n"
+        );
+    }
+
+    #[cfg(not(feature = "no-log"))]
+    #[test]
+    fn test_counting_logger_counts_through_logger_trait() {
+        fn self_test(log: &mut impl Logger) {
+            log.info(format_args!("start"));
+            log.warn(format_args!("retry"));
+            log.log_hex_dump(0, b"[Warn] ");
+            log.error(format_args!("nack"));
+        }
+
+        let mut out = StringWriter::<128>::new();
+        let mut log = CountingLogger::new(&mut out);
+        self_test(&mut log);
+        assert_eq!(log.counts(), (1, 1, 1));
+    }
+
+    #[test]
+    fn test_ring_logger_keeps_latest_bytes() {
+        let mut ring = RingLogger::<8>::new();
+        write!(ring, "abc").unwrap();
+        let mut out = StringWriter::<16>::new();
+        ring.dump(&mut out).unwrap();
+        assert_eq!(out.as_str(), "abc");
+
+        write!(ring, "defghij").unwrap();
+        assert_eq!(ring.len(), 8);
+        out.clear();
+        ring.dump(&mut out).unwrap();
+        assert_eq!(out.as_str(), "cdefghij");
+
+        ring.clear();
+        assert!(ring.is_empty());
+    }
+
+    #[test]
+    fn test_tee_writer_writes_both() {
+        let mut uart = StringWriter::<4>::new();
+        let mut ring = RingLogger::<16>::new();
+        {
+            let mut tee = TeeWriter::new(&mut uart, &mut ring);
+            write!(tee, "OK\r\n").unwrap();
+            // The 4-byte UART is full: the error surfaces, the ring is skipped.
+            assert!(write!(tee, "more").is_err());
+        }
+        assert_eq!(uart.as_str(), "OK\r\n");
+        let mut out = StringWriter::<16>::new();
+        ring.dump(&mut out).unwrap();
+        assert_eq!(out.as_str(), "OK\r\n");
+    }
+
+    #[cfg(not(feature = "no-log"))]
+    #[test]
+    fn test_log_hex_dump_lines() {
+        let data: [u8; 23] = *b"0123456789ABCDEFHello\x00\xFF";
+        let mut out = StringWriter::<256>::new();
+        out.log_hex_dump(0x0100, &data);
+        assert_eq!(
+            out.as_str(),
+            "0100: 30 31 32 33 34 35 36 37 38 39 41 42 43 44 45 46  |0123456789ABCDEF|\r\n\
+             0110: 48 65 6C 6C 6F 00 FF                             |Hello..|\r\n"
+        );
+    }
+
+    #[cfg(not(feature = "no-log"))]
+    #[test]
+    fn test_logger_prefixes_levels() {
+        let mut out = StringWriter::<64>::new();
+        out.info(format_args!("found {}", 2));
+        out.error(format_args!("nack"));
+        assert_eq!(out.as_str(), "[Info] found 2\r\n[Error] nack\r\n");
+    }
+}
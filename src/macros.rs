@@ -7,6 +7,39 @@
 //! - Debugging assistance (assert, delayed loop, cycle measurement)
 //!
 
+/// Internal logging macro through which all diagnostic output of the crate goes.
+///
+/// With the `no-log` feature every invocation compiles to nothing: the
+/// arguments are still type-checked, but the write sits behind a constant
+/// `false` branch that is removed entirely at build time.
+///
+/// Output always goes to the writer passed in, never to a global sink, so
+/// tests capture a runner's exact log by handing it a `StringWriter`
+/// (feature `testing`). Direct `write!` calls are kept only for output the
+/// caller asked for explicitly (CSV, graph dumps, `Display` impls), which must
+/// survive `no-log`.
+///
+/// - `dvcdbg_log!(w, "fmt", args..)` writes formatted text.
+/// - `dvcdbg_log!(@hex w, bytes)` writes bytes as space-separated hex (`3C 48`).
+/// - `dvcdbg_log!(@ascii w, "fmt", args..)` writes formatted text with non-ASCII escaped.
+macro_rules! dvcdbg_log {
+    (@hex $w:expr, $bytes:expr) => {
+        if cfg!(not(feature = "no-log")) {
+            $crate::compat::util::write_bytes_hex_fmt($w, $bytes).ok();
+        }
+    };
+    (@ascii $w:expr, $($arg:tt)*) => {
+        if cfg!(not(feature = "no-log")) {
+            $crate::compat::util::write_formatted_ascii_safe($w, core::format_args!($($arg)*)).ok();
+        }
+    };
+    ($w:expr, $($arg:tt)*) => {
+        if cfg!(not(feature = "no-log")) {
+            core::fmt::Write::write_fmt($w, core::format_args!($($arg)*)).ok();
+        }
+    };
+}
+
 /// Wrap a type implementing `SerialCompat` and provide a `core::fmt::Write` adapter.
 ///
 /// # Purpose
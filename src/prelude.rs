@@ -2,13 +2,39 @@
 //! Users can simply `use dvcdbg::prelude::*;` to access the main types and macros.
 
 pub use crate::{
-    adapt_serial, assert_log, get_one_sort, loop_with_delay, measure_cycles, nodes, pruning_sort,
-    quick_diag, write_bin, write_hex,
+    adapt_serial, assert_log, deps, get_one_sort, loop_with_delay, measure_cycles, nodes,
+    nodes_from_consts, pruning_sort, quick_diag, write_bin, write_hex,
 };
 
+#[allow(deprecated)]
+pub use crate::deps_u8;
+
 pub use crate::compat::adapt::FmtWriteAdapter;
+pub use crate::compat::bitbang::BitbangI2c;
+pub use crate::compat::delay_compat::{DelayCompat, SpinDelay};
 pub use crate::compat::err_compat::HalErrorExt;
+pub use crate::compat::gpio_compat::{InputPinCompat, OutputPinCompat};
+pub use crate::compat::guard::GuardedI2c;
 pub use crate::compat::i2c_compat::I2cCompat;
+#[cfg(feature = "ehal_async")]
+pub use crate::compat::i2c_compat::I2cCompatAsync;
+#[cfg(feature = "testing")]
+pub use crate::compat::mock::{OracleI2c, TraceReplayI2c};
 pub use crate::compat::serial_compat::SerialCompat;
-pub use crate::error::{BufferError, ErrorKind, ExecutorError, ExplorerError, I2cError, UartError};
-pub use crate::scanner::{scan_i2c, scan_init_sequence};
+pub use crate::compat::time_compat::TimeSource;
+pub use crate::error::{
+    AddressedError, BufferError, ErrorKind, ExecutorError, ExplorerError, I2cError, UartError,
+};
+#[cfg(feature = "testing")]
+pub use crate::logger::StringWriter;
+pub use crate::logger::{
+    CountingLogger, Logger, RingLogger, RunIdWriter, TeeWriter, TimestampedLogger,
+};
+#[cfg(feature = "ehal_async")]
+pub use crate::scanner::scan_i2c_async;
+pub use crate::scanner::{
+    BusLineStatus, ScanMux, ScanReport, diagnose_bus_lines, hardware_reset, read_register,
+    read_registers, scan_diff, scan_i2c, scan_i2c_bitmap, scan_i2c_multi, scan_i2c_probe,
+    scan_i2c_range, scan_i2c_report, scan_i2c_timed, scan_i2c_yielding, scan_init_sequence,
+    scan_speed_sweep, warn_if_risky_ctrl_byte, write_scan_binary, write_scan_csv, write_scan_diff,
+};
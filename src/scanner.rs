@@ -5,51 +5,161 @@ use crate::compat::HalErrorExt;
 pub const I2C_SCAN_ADDR_START: u8 = 0x03;
 pub const I2C_SCAN_ADDR_END: u8 = 0x77;
 pub const I2C_MAX_DEVICES: usize = 128;
+/// First byte of a binary scan packet written by [`write_scan_binary`].
+pub const SCAN_PACKET_MAGIC: u8 = 0xA5;
+/// Control bytes that common devices treat as commands when written on their
+/// own: `0x06` (general-call software reset), `0xAE` (SSD1306/SH1106 display
+/// off) and `0xE2` (ST7565/UC1701 reset).
+pub const RISKY_CTRL_BYTES: [u8; 3] = [0x06, 0xAE, 0xE2];
+
+/// Logs a prominent warning if `ctrl_byte` is in `risky` and returns whether it was.
+///
+/// Scans that write control bytes run this against [`RISKY_CTRL_BYTES`];
+/// call it with your own set before scanning a bus with unusual devices.
+pub fn warn_if_risky_ctrl_byte<W>(writer: &mut W, ctrl_byte: u8, risky: &[u8]) -> bool
+where
+    W: core::fmt::Write,
+{
+    let hit = risky.contains(&ctrl_byte);
+    if hit {
+        dvcdbg_log!(
+            writer,
+            "[W] !!! ctrl byte {ctrl_byte:02X} is a known device command; every device on the bus may act on it !!!\r\n"
+        );
+    }
+    hit
+}
 
 /// Scans the I2C bus for devices that respond to a given data write.
 ///
 /// It iterates through all possible I2C addresses and attempts to write the
 /// provided `data`.
-fn internal_scan<I2C>(
+pub(crate) fn internal_scan<I2C>(
     i2c: &mut I2C,
 ) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, crate::error::ErrorKind>
 where
     I2C: crate::compat::I2cCompat,
     <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
 {
-    let mut found_addrs = heapless::Vec::<u8, I2C_MAX_DEVICES>::new();
-    let mut last_error: Option<crate::error::ErrorKind> = None;
+    internal_scan_with(i2c, |i2c, addr| i2c.probe(addr))
+}
 
-    for addr in I2C_SCAN_ADDR_START..=I2C_SCAN_ADDR_END {
-        match i2c.probe(addr) {
-            Ok(true) => {
-                if found_addrs.push(addr).is_err() {
-                    return Err(crate::error::ErrorKind::Buffer(
-                        crate::error::BufferError::Overflow,
-                    ));
-                }
-            }
-            Ok(false) => {
-                continue;
-            }
-            Err(e) => {
-                let error_kind = e.to_compat(Some(addr));
-                if error_kind == crate::error::ErrorKind::I2c(crate::error::I2cError::Nack) {
-                    continue;
-                }
-                last_error = Some(error_kind);
+/// Same as `internal_scan`, returning the responding addresses as a 16-byte
+/// [`BitFlags`](crate::compat::util::BitFlags) instead of a 128-byte list.
+pub(crate) fn internal_scan_flags<I2C>(
+    i2c: &mut I2C,
+) -> Result<crate::compat::util::BitFlags, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+{
+    probe_all(i2c, |i2c, addr| i2c.probe(addr))?.finish_flags()
+}
+
+/// Same as `internal_scan`, but every presence check goes through `probe`,
+/// letting callers instrument or wrap the individual transactions.
+fn internal_scan_with<I2C, F>(
+    i2c: &mut I2C,
+    probe: F,
+) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    F: FnMut(&mut I2C, u8) -> Result<bool, I2C::Error>,
+{
+    probe_all(i2c, probe)?.finish()
+}
+
+fn probe_all<I2C, F>(i2c: &mut I2C, probe: F) -> Result<ScanState, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    F: FnMut(&mut I2C, u8) -> Result<bool, I2C::Error>,
+{
+    probe_range(i2c, I2C_SCAN_ADDR_START, I2C_SCAN_ADDR_END, probe)
+}
+
+fn probe_range<I2C, F>(
+    i2c: &mut I2C,
+    start: u8,
+    end: u8,
+    mut probe: F,
+) -> Result<ScanState, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    F: FnMut(&mut I2C, u8) -> Result<bool, I2C::Error>,
+{
+    let mut state = ScanState::default();
+    for addr in start..=end {
+        state.record(addr, probe(i2c, addr).map_err(|e| e.to_compat(Some(addr))))?;
+    }
+    Ok(state)
+}
+
+/// Accumulates probe results over a scan; shared by the blocking and async scanners.
+#[derive(Default)]
+struct ScanState {
+    found_addrs: crate::compat::util::BitFlags,
+    last_error: Option<crate::error::ErrorKind>,
+}
+
+impl ScanState {
+    /// Records one probe. NACKs are ignored; other errors are remembered in
+    /// case nothing is found.
+    fn record(
+        &mut self,
+        addr: u8,
+        result: Result<bool, crate::error::ErrorKind>,
+    ) -> Result<(), crate::error::ErrorKind> {
+        match result {
+            Ok(true) => self
+                .found_addrs
+                .set(addr as usize)
+                .map_err(|_| crate::error::ErrorKind::Buffer(crate::error::BufferError::Overflow)),
+            Ok(false) => Ok(()),
+            Err(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)) => Ok(()),
+            Err(error_kind) => {
+                self.last_error = Some(error_kind);
+                Ok(())
             }
         }
     }
 
-    if found_addrs.is_empty() {
-        Err(last_error.unwrap_or(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)))
-    } else {
-        Ok(found_addrs)
+    fn finish(self) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, crate::error::ErrorKind> {
+        let found = self.finish_flags()?;
+        // At most 128 addresses, so every push fits.
+        Ok(found.iter_set().map(|addr| addr as u8).collect())
+    }
+
+    fn finish_flags(self) -> Result<crate::compat::util::BitFlags, crate::error::ErrorKind> {
+        if self.found_addrs.is_empty() {
+            Err(self
+                .last_error
+                .unwrap_or(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)))
+        } else {
+            Ok(self.found_addrs)
+        }
+    }
+}
+
+#[cfg(feature = "ehal_async")]
+pub(crate) async fn internal_scan_async<I2C>(
+    i2c: &mut I2C,
+) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompatAsync,
+    <I2C as crate::compat::I2cCompatAsync>::Error: crate::compat::HalErrorExt,
+{
+    let mut state = ScanState::default();
+    for addr in I2C_SCAN_ADDR_START..=I2C_SCAN_ADDR_END {
+        let result = i2c.probe(addr).await.map_err(|e| e.to_compat(Some(addr)));
+        state.record(addr, result)?;
     }
+    state.finish()
 }
 
-/// Scans the I2C bus for devices by attempting to write a single control byte to each address.
+/// Scans the I2C bus for devices by probing each address.
 ///
 /// # Parameters
 ///
@@ -57,6 +167,17 @@ where
 /// - `serial`: The serial writer for logging.
 /// - `ctrl_byte`: The control byte.
 /// - `log_level`: The desired logging level.
+///
+/// Presence is checked with a zero-length write, so `ctrl_byte` only labels
+/// the log and is never sent. Scans that do send it ([`scan_i2c_multi`],
+/// [`scan_init_sequence`]) warn about [`RISKY_CTRL_BYTES`] first.
+///
+/// # Ordering
+///
+/// Addresses are probed from `I2C_SCAN_ADDR_START` upwards, so the result is
+/// always in ascending numeric order. It does not reflect physical bus
+/// position or the order devices came up; on a multiplexed bus, keep track of
+/// the selected channel yourself (each scan only sees the active channel).
 pub fn scan_i2c<I2C, W>(
     i2c: &mut I2C,
     writer: &mut W,
@@ -67,19 +188,602 @@ where
     <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
     W: core::fmt::Write,
 {
-    core::fmt::Write::write_str(writer, "Scanning I2C bus with a ").ok();
-    crate::compat::util::write_bytes_hex_fmt(writer, &[ctrl_byte]).ok();
-    core::fmt::Write::write_str(writer, " ...\r\n").ok();
+    scan_i2c_range(
+        i2c,
+        writer,
+        ctrl_byte,
+        I2C_SCAN_ADDR_START,
+        I2C_SCAN_ADDR_END,
+    )
+}
+
+/// Same as [`scan_i2c`], probing only `start..=end`.
+///
+/// Use it to stay clear of reserved regions or addresses where a mux or
+/// bridge ACKs spuriously. Fails with `ErrorKind::InvalidConfig` if
+/// `start > end` or either bound is outside `0x00..=0x7F`.
+pub fn scan_i2c_range<I2C, W>(
+    i2c: &mut I2C,
+    writer: &mut W,
+    ctrl_byte: u8,
+    start: u8,
+    end: u8,
+) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    W: core::fmt::Write,
+{
+    if start > end || end > 0x7F {
+        dvcdbg_log!(writer, "[E] Invalid scan range {start:02X}..={end:02X}\r\n");
+        return Err(crate::error::ErrorKind::InvalidConfig);
+    }
+    dvcdbg_log!(writer, "Scanning I2C bus with a {ctrl_byte:02X} ...\r\n");
 
-    let found_addrs = internal_scan(i2c)?;
+    let found_addrs = probe_range(i2c, start, end, |i2c, addr| i2c.probe(addr))?.finish()?;
 
-    core::fmt::Write::write_str(writer, "Found device @ ").ok();
-    crate::compat::util::write_bytes_hex_fmt(writer, &found_addrs).ok();
-    core::fmt::Write::write_str(writer, "\r\n").ok();
+    dvcdbg_log!(writer, "Found device @ ");
+    dvcdbg_log!(@hex writer, &found_addrs);
+    dvcdbg_log!(writer, "\r\n");
 
     Ok(found_addrs)
 }
 
+/// Strict variant of [`scan_i2c`]: probes every address with
+/// [`I2cCompat::probe`](crate::compat::I2cCompat::probe) and fails on the
+/// first bus error.
+///
+/// `Ok(true)` counts as found; `Ok(false)` and NACK errors as absent. Any
+/// other error is returned right away, whereas `scan_i2c` only reports errors
+/// when no device answered. An empty bus is `Ok` with an empty list. Nothing
+/// but probes is written, so no control byte reaches any device.
+pub fn scan_i2c_probe<I2C, W>(
+    i2c: &mut I2C,
+    writer: &mut W,
+) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    W: core::fmt::Write,
+{
+    dvcdbg_log!(writer, "Probing I2C bus ...\r\n");
+
+    let mut found_addrs = heapless::Vec::new();
+    for addr in I2C_SCAN_ADDR_START..=I2C_SCAN_ADDR_END {
+        match i2c.probe(addr).map_err(|e| e.to_compat(Some(addr))) {
+            Ok(true) => {
+                // Cannot overflow: fewer addresses than I2C_MAX_DEVICES.
+                found_addrs.push(addr).ok();
+            }
+            Ok(false) | Err(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)) => {}
+            Err(e) => {
+                dvcdbg_log!(writer, "[E] Bus error @ {addr:02X}: {e}\r\n");
+                return Err(e);
+            }
+        }
+    }
+
+    dvcdbg_log!(writer, "Found device @ ");
+    dvcdbg_log!(@hex writer, &found_addrs);
+    dvcdbg_log!(writer, "\r\n");
+
+    Ok(found_addrs)
+}
+
+/// Async counterpart of [`scan_i2c`] for `I2cCompatAsync` buses: each probe
+/// is awaited, so the executor keeps running other tasks during the scan.
+///
+/// Errors are handled exactly as in `scan_i2c`.
+#[cfg(feature = "ehal_async")]
+pub async fn scan_i2c_async<I2C, W>(
+    i2c: &mut I2C,
+    writer: &mut W,
+    ctrl_byte: u8,
+) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompatAsync,
+    <I2C as crate::compat::I2cCompatAsync>::Error: crate::compat::HalErrorExt,
+    W: core::fmt::Write,
+{
+    dvcdbg_log!(writer, "Scanning I2C bus with a {ctrl_byte:02X} ...\r\n");
+
+    let found_addrs = internal_scan_async(i2c).await?;
+
+    dvcdbg_log!(writer, "Found device @ ");
+    dvcdbg_log!(@hex writer, &found_addrs);
+    dvcdbg_log!(writer, "\r\n");
+
+    Ok(found_addrs)
+}
+
+/// Bus idle time between consecutive transactions of a timed scan, in `TimeSource` ticks.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IdleStats {
+    /// Shortest gap between the end of one transaction and the start of the next.
+    pub min: u32,
+    /// Longest gap.
+    pub max: u32,
+    /// Sum of all gaps.
+    pub total: u64,
+    /// Number of gaps measured (transactions - 1).
+    pub samples: u32,
+}
+
+impl IdleStats {
+    fn record(&mut self, idle: u32) {
+        if self.samples == 0 || idle < self.min {
+            self.min = idle;
+        }
+        if idle > self.max {
+            self.max = idle;
+        }
+        self.total += idle as u64;
+        self.samples += 1;
+    }
+
+    /// Average gap, or 0 if nothing was measured.
+    pub fn avg(&self) -> u32 {
+        if self.samples == 0 {
+            0
+        } else {
+            (self.total / self.samples as u64) as u32
+        }
+    }
+}
+
+impl core::fmt::Display for IdleStats {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "min {} / max {} / avg {} ticks ({} gaps)",
+            self.min,
+            self.max,
+            self.avg(),
+            self.samples
+        )
+    }
+}
+
+/// Per-address outcome counts of a scan, returned by [`scan_i2c_report`].
+///
+/// Clean NACKs are normal on any bus; many `bus_errors` usually point at
+/// wiring or missing pull-ups.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScanReport {
+    /// Addresses that acknowledged, in ascending order.
+    pub found: heapless::Vec<u8, I2C_MAX_DEVICES>,
+    /// Number of addresses probed.
+    pub addresses_probed: usize,
+    /// Addresses that did not acknowledge.
+    pub nacks: usize,
+    /// Probes that failed with anything other than a NACK.
+    pub bus_errors: usize,
+}
+
+impl core::fmt::Display for ScanReport {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(
+            f,
+            "{} found / {} probed / {} NACK / {} bus errors",
+            self.found.len(),
+            self.addresses_probed,
+            self.nacks,
+            self.bus_errors
+        )
+    }
+}
+
+/// Scans the I2C bus and counts how every address responded.
+///
+/// Unlike [`scan_i2c`], errors never abort or fail the scan; they are counted
+/// in the returned [`ScanReport`], which is also logged.
+pub fn scan_i2c_report<I2C, W>(i2c: &mut I2C, writer: &mut W) -> ScanReport
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    W: core::fmt::Write,
+{
+    let mut report = ScanReport::default();
+    for addr in I2C_SCAN_ADDR_START..=I2C_SCAN_ADDR_END {
+        report.addresses_probed += 1;
+        match i2c.probe(addr).map_err(|e| e.to_compat(Some(addr))) {
+            Ok(true) => {
+                // Cannot overflow: fewer addresses than I2C_MAX_DEVICES.
+                report.found.push(addr).ok();
+            }
+            Ok(false) | Err(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)) => {
+                report.nacks += 1;
+            }
+            Err(_) => report.bus_errors += 1,
+        }
+    }
+
+    dvcdbg_log!(writer, "[scan] {report}\r\n");
+    report
+}
+
+/// Scans the I2C bus and returns the set of responding addresses as a bitmap.
+///
+/// Bit `n` is set when address `n` acknowledged; NACKs leave it clear. An
+/// empty bus yields an empty bitmap rather than an error, so two scans can
+/// always be compared, e.g. to spot a device that appeared or disappeared.
+/// As with [`scan_i2c`], a bus error (anything but a NACK) is returned as
+/// `Err` when no address acknowledged.
+pub fn scan_i2c_bitmap<I2C, W>(
+    i2c: &mut I2C,
+    writer: &mut W,
+) -> Result<crate::compat::util::BitFlags, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    W: core::fmt::Write,
+{
+    dvcdbg_log!(writer, "Scanning I2C bus into bitmap ...\r\n");
+
+    let bitmap = match internal_scan_flags(i2c) {
+        Ok(bitmap) => bitmap,
+        Err(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)) => {
+            crate::compat::util::BitFlags::new()
+        }
+        Err(e) => return Err(e),
+    };
+
+    dvcdbg_log!(writer, "Found {} device(s)\r\n", bitmap.count_ones());
+    Ok(bitmap)
+}
+
+/// Writes a scan result as a compact binary packet for a host-side decoder.
+///
+/// Layout: [`SCAN_PACKET_MAGIC`], the address count (one byte), then one byte
+/// per address. At most 255 addresses are written; a scan never finds more
+/// than [`I2C_MAX_DEVICES`].
+pub fn write_scan_binary<W: embedded_io::Write>(found: &[u8], w: &mut W) -> Result<(), W::Error> {
+    let found = &found[..found.len().min(u8::MAX as usize)];
+    w.write_all(&[SCAN_PACKET_MAGIC, found.len() as u8])?;
+    w.write_all(found)
+}
+
+/// Writes a scan result as CSV for a spreadsheet: an `address,present`
+/// header, then one row per scanned address (`0x3C,yes`, `0x3D,no`, ...).
+pub fn write_scan_csv<W: core::fmt::Write>(found: &[u8], w: &mut W) -> core::fmt::Result {
+    writeln!(w, "address,present")?;
+    for addr in I2C_SCAN_ADDR_START..=I2C_SCAN_ADDR_END {
+        let present = if found.contains(&addr) { "yes" } else { "no" };
+        writeln!(w, "0x{addr:02X},{present}")?;
+    }
+    Ok(())
+}
+
+/// Compares two [`scan_i2c_bitmap`] results.
+///
+/// Returns `(appeared, disappeared)`: addresses present only in `after`, and
+/// addresses present only in `before`.
+pub fn scan_diff(
+    before: &crate::compat::util::BitFlags,
+    after: &crate::compat::util::BitFlags,
+) -> (crate::compat::util::BitFlags, crate::compat::util::BitFlags) {
+    (after.difference(before), before.difference(after))
+}
+
+/// Logs the changes between two bitmap scans, one line per address
+/// (e.g. `0x48 disappeared`), and returns them as [`scan_diff`] does.
+pub fn write_scan_diff<W: core::fmt::Write>(
+    writer: &mut W,
+    before: &crate::compat::util::BitFlags,
+    after: &crate::compat::util::BitFlags,
+) -> (crate::compat::util::BitFlags, crate::compat::util::BitFlags) {
+    let (appeared, disappeared) = scan_diff(before, after);
+    for addr in appeared.iter_set() {
+        dvcdbg_log!(writer, "0x{addr:02X} appeared\r\n");
+    }
+    for addr in disappeared.iter_set() {
+        dvcdbg_log!(writer, "0x{addr:02X} disappeared\r\n");
+    }
+    if appeared.is_empty() && disappeared.is_empty() {
+        dvcdbg_log!(writer, "No bus changes\r\n");
+    }
+    (appeared, disappeared)
+}
+
+/// Reads the single byte register `reg` of the device at `addr`, e.g. a
+/// WHO_AM_I or chip-ID register after a scan.
+///
+/// The value is logged as `[reg] @3C:0F = 33`.
+pub fn read_register<I2C, W>(
+    i2c: &mut I2C,
+    writer: &mut W,
+    addr: u8,
+    reg: u8,
+) -> Result<u8, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    W: core::fmt::Write,
+{
+    let mut value = [0u8; 1];
+    read_registers(i2c, writer, addr, reg, &mut value)?;
+    Ok(value[0])
+}
+
+/// Burst-reads `buf.len()` bytes starting at register `start_reg` with one
+/// `write_read`, relying on the device auto-incrementing its register pointer.
+pub fn read_registers<I2C, W>(
+    i2c: &mut I2C,
+    writer: &mut W,
+    addr: u8,
+    start_reg: u8,
+    buf: &mut [u8],
+) -> Result<(), crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    W: core::fmt::Write,
+{
+    i2c.write_read(addr, &[start_reg], buf).map_err(|e| {
+        let err = e.to_compat_at(addr);
+        dvcdbg_log!(
+            writer,
+            "[reg] @{addr:02X}:{start_reg:02X} failed: {err}\r\n"
+        );
+        err.kind
+    })?;
+    dvcdbg_log!(writer, "[reg] @{addr:02X}:{start_reg:02X} = ");
+    dvcdbg_log!(@hex writer, buf);
+    dvcdbg_log!(writer, "\r\n");
+    Ok(())
+}
+
+/// Same as [`scan_i2c`], but measures the bus idle time between consecutive
+/// presence checks using `time`.
+///
+/// A large idle time relative to the transaction length means the scan loop
+/// is code-bound rather than bus-bound, so adding delays is rarely needed.
+/// The default `scan_i2c` path carries no timing overhead.
+pub fn scan_i2c_timed<I2C, W, T>(
+    i2c: &mut I2C,
+    writer: &mut W,
+    ctrl_byte: u8,
+    time: &T,
+) -> Result<(heapless::Vec<u8, I2C_MAX_DEVICES>, IdleStats), crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    W: core::fmt::Write,
+    T: crate::compat::TimeSource,
+{
+    dvcdbg_log!(
+        writer,
+        "Timed scan of I2C bus with a {ctrl_byte:02X} ...\r\n"
+    );
+
+    let mut stats = IdleStats::default();
+    let mut last_end: Option<u32> = None;
+    let found_addrs = internal_scan_with(i2c, |i2c, addr| {
+        let start = time.now();
+        if let Some(end) = last_end {
+            stats.record(start.wrapping_sub(end));
+        }
+        let res = i2c.probe(addr);
+        last_end = Some(time.now());
+        res
+    })?;
+
+    dvcdbg_log!(writer, "Found device @ ");
+    dvcdbg_log!(@hex writer, &found_addrs);
+    dvcdbg_log!(writer, "\r\nBus idle: {stats}\r\n");
+
+    Ok((found_addrs, stats))
+}
+
+/// Same as [`scan_i2c`], but calls `on_yield` after every `every` probed
+/// addresses, e.g. to feed a watchdog or pump a scheduler during slow scans.
+///
+/// `every == 0` never yields.
+pub fn scan_i2c_yielding<I2C, W, F>(
+    i2c: &mut I2C,
+    writer: &mut W,
+    ctrl_byte: u8,
+    every: usize,
+    mut on_yield: F,
+) -> Result<heapless::Vec<u8, I2C_MAX_DEVICES>, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    W: core::fmt::Write,
+    F: FnMut(),
+{
+    dvcdbg_log!(writer, "Scanning I2C bus with a {ctrl_byte:02X} ...\r\n");
+
+    let mut probed = 0usize;
+    let found_addrs = internal_scan_with(i2c, |i2c, addr| {
+        let res = i2c.probe(addr);
+        probed += 1;
+        if every != 0 && probed.is_multiple_of(every) {
+            on_yield();
+        }
+        res
+    })?;
+
+    dvcdbg_log!(writer, "Found device @ ");
+    dvcdbg_log!(@hex writer, &found_addrs);
+    dvcdbg_log!(writer, "\r\n");
+
+    Ok(found_addrs)
+}
+
+/// Probes `addr` `samples` times at each bus speed and counts the ACKs, to
+/// find the speed range at which a marginal device answers reliably.
+///
+/// The crate has no portable way to change the bus clock, so `set_speed` is
+/// called with each entry of `speeds` (in Hz) before its samples, e.g. to
+/// reconfigure the HAL's I2C peripheral. A probe that errors counts as a
+/// miss. Returns `(speed, acks)` pairs in the order of `speeds`; `samples`
+/// is capped at 255 so the count fits in a `u8`. Fails with a buffer
+/// overflow if there are more than `N` speeds.
+///
+/// ```ignore
+/// let sweep = scan_speed_sweep::<_, _, _, 3>(&mut i2c, &mut serial, 0x3C,
+///     &[100_000, 400_000, 1_000_000], 20, |i2c, hz| i2c.set_frequency(hz))?;
+/// // [speed] 3C @ 100000 Hz: 20/20 ACK
+/// // [speed] 3C @ 400000 Hz: 12/20 ACK
+/// ```
+pub fn scan_speed_sweep<I2C, W, F, const N: usize>(
+    i2c: &mut I2C,
+    writer: &mut W,
+    addr: u8,
+    speeds: &[u32],
+    samples: usize,
+    mut set_speed: F,
+) -> Result<heapless::Vec<(u32, u8), N>, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    W: core::fmt::Write,
+    F: FnMut(&mut I2C, u32),
+{
+    if speeds.len() > N {
+        return Err(crate::error::ErrorKind::Buffer(
+            crate::error::BufferError::Overflow,
+        ));
+    }
+    let samples = samples.min(u8::MAX as usize) as u8;
+
+    let mut sweep = heapless::Vec::new();
+    for &hz in speeds {
+        set_speed(i2c, hz);
+        let acks = (0..samples)
+            .filter(|_| matches!(i2c.probe(addr), Ok(true)))
+            .count() as u8;
+        dvcdbg_log!(
+            writer,
+            "[speed] {addr:02X} @ {hz} Hz: {acks}/{samples} ACK\r\n"
+        );
+        // Cannot fail: `speeds` fits in N.
+        sweep.push((hz, acks)).ok();
+    }
+    Ok(sweep)
+}
+
+/// Scans the I2C bus with several control bytes per address.
+///
+/// For each address, every byte in `ctrl_bytes` is written on its own and the
+/// `(addr, ctrl_byte)` pairs that were acknowledged are returned. This shows
+/// not only which devices are present but which leading byte (command vs.
+/// data register, mode selector, ...) each one responds to.
+///
+/// Pairs are ordered by address, then by position in `ctrl_bytes`.
+pub fn scan_i2c_multi<I2C, W, const N: usize>(
+    i2c: &mut I2C,
+    writer: &mut W,
+    ctrl_bytes: &[u8],
+) -> Result<heapless::Vec<(u8, u8), N>, crate::error::ErrorKind>
+where
+    I2C: crate::compat::I2cCompat,
+    <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+    W: core::fmt::Write,
+{
+    dvcdbg_log!(writer, "Scanning I2C bus with ctrl bytes ");
+    dvcdbg_log!(@hex writer, ctrl_bytes);
+    dvcdbg_log!(writer, " ...\r\n");
+    for &ctrl in ctrl_bytes {
+        warn_if_risky_ctrl_byte(writer, ctrl, &RISKY_CTRL_BYTES);
+    }
+
+    let mut responses = heapless::Vec::<(u8, u8), N>::new();
+    let mut last_error: Option<crate::error::ErrorKind> = None;
+
+    for addr in I2C_SCAN_ADDR_START..=I2C_SCAN_ADDR_END {
+        for &ctrl in ctrl_bytes {
+            match i2c.write(addr, &[ctrl]) {
+                Ok(_) => {
+                    dvcdbg_log!(writer, "Response @ {addr:02X} for {ctrl:02X}\r\n");
+                    if responses.push((addr, ctrl)).is_err() {
+                        return Err(crate::error::ErrorKind::Buffer(
+                            crate::error::BufferError::Overflow,
+                        ));
+                    }
+                }
+                Err(e) => {
+                    let error_kind = e.to_compat(Some(addr));
+                    if error_kind != crate::error::ErrorKind::I2c(crate::error::I2cError::Nack) {
+                        last_error = Some(error_kind);
+                    }
+                }
+            }
+        }
+    }
+
+    if responses.is_empty() {
+        Err(last_error.unwrap_or(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)))
+    } else {
+        Ok(responses)
+    }
+}
+
+/// Scans the channels of a TCA9548A-style I2C multiplexer.
+///
+/// The mux selects downstream channels through a one-byte bitmask written to
+/// its own address. [`ScanMux::scan`] selects each channel in turn, runs
+/// [`scan_i2c`] and tags every address with its channel.
+pub struct ScanMux {
+    mux_addr: u8,
+    channels: u8,
+}
+
+impl ScanMux {
+    /// Channels addressable through a one-byte select mask.
+    pub const MAX_CHANNELS: u8 = 8;
+
+    /// `channels` is clamped to [`ScanMux::MAX_CHANNELS`].
+    pub const fn new(mux_addr: u8, channels: u8) -> Self {
+        let channels = if channels > Self::MAX_CHANNELS {
+            Self::MAX_CHANNELS
+        } else {
+            channels
+        };
+        Self { mux_addr, channels }
+    }
+
+    /// Scans every channel and returns `(channel, addr)` pairs.
+    ///
+    /// Pairs are ordered by channel, then by ascending address. The mux's own
+    /// address is left out, and all channels are deselected afterwards.
+    pub fn scan<I2C, W, const N: usize>(
+        &self,
+        i2c: &mut I2C,
+        writer: &mut W,
+        ctrl_byte: u8,
+    ) -> Result<heapless::Vec<(u8, u8), N>, crate::error::ErrorKind>
+    where
+        I2C: crate::compat::I2cCompat,
+        <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
+        W: core::fmt::Write,
+    {
+        let mut found = heapless::Vec::<(u8, u8), N>::new();
+
+        for channel in 0..self.channels {
+            dvcdbg_log!(writer, "[mux {:02X}] Channel {channel}\r\n", self.mux_addr);
+            i2c.write(self.mux_addr, &[1 << channel])
+                .map_err(|e| e.to_compat(Some(self.mux_addr)))?;
+
+            let addrs = match scan_i2c(i2c, writer, ctrl_byte) {
+                Ok(addrs) => addrs,
+                Err(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack)) => continue,
+                Err(e) => return Err(e),
+            };
+            for &addr in addrs.iter().filter(|&&addr| addr != self.mux_addr) {
+                found.push((channel, addr)).map_err(|_| {
+                    crate::error::ErrorKind::Buffer(crate::error::BufferError::Overflow)
+                })?;
+            }
+        }
+
+        i2c.write(self.mux_addr, &[0])
+            .map_err(|e| e.to_compat(Some(self.mux_addr)))?;
+
+        Ok(found)
+    }
+}
+
 /// Scans the I2C bus for devices that respond to a given initialization sequence.
 ///
 /// This function first performs an initial scan to find all responding devices,
@@ -108,17 +812,19 @@ where
     <I2C as crate::compat::I2cCompat>::Error: crate::compat::HalErrorExt,
     W: core::fmt::Write,
 {
-    core::fmt::Write::write_str(writer, "Start I2C scan with INIT_SEQ...\r\n").ok();
-    core::fmt::Write::write_str(writer, "Initializing scan with ctrl byte ").ok();
-    crate::compat::util::write_bytes_hex_fmt(writer, &[ctrl_byte]).ok();
-    core::fmt::Write::write_str(writer, "\r\n").ok();
+    dvcdbg_log!(writer, "Start I2C scan with INIT_SEQ...\r\n");
+    dvcdbg_log!(
+        writer,
+        "Initializing scan with ctrl byte {ctrl_byte:02X}\r\n"
+    );
+    warn_if_risky_ctrl_byte(writer, ctrl_byte, &RISKY_CTRL_BYTES);
 
     let found_addrs = crate::scanner::scan_i2c(i2c, writer, ctrl_byte).inspect_err(|&e| {
-        write!(writer, "Failed to scan I2C: {e}\r\n").ok();
+        dvcdbg_log!(writer, "Failed to scan I2C: {e}\r\n");
     })?;
 
     if found_addrs.is_empty() {
-        core::fmt::Write::write_str(writer, "No devices found.\r\n").ok();
+        dvcdbg_log!(writer, "No devices found.\r\n");
         return Err(crate::error::ErrorKind::I2c(crate::error::I2cError::Nack));
     }
 
@@ -126,42 +832,31 @@ where
     let mut last_error: Option<crate::error::ErrorKind> = None;
 
     for &addr in found_addrs.iter() {
-        core::fmt::Write::write_str(writer, "Testing init SEQ @ ").ok();
-        crate::compat::util::write_bytes_hex_fmt(writer, &[addr]).ok();
-        core::fmt::Write::write_str(writer, "...\r\n").ok();
+        dvcdbg_log!(writer, "Testing init SEQ @ {addr:02X}...\r\n");
 
         for &cmd in init_sequence.iter() {
             let command_data = [ctrl_byte, cmd];
-            core::fmt::Write::write_str(writer, "  Sending command ").ok();
-            crate::compat::util::write_bytes_hex_fmt(writer, &[cmd]).ok();
-            core::fmt::Write::write_str(writer, " to ").ok();
-            crate::compat::util::write_bytes_hex_fmt(writer, &[addr]).ok();
-            core::fmt::Write::write_str(writer, "...\r\n").ok();
+            dvcdbg_log!(writer, "  Sending command {cmd:02X} to {addr:02X}...\r\n");
 
             match i2c.write(addr, &command_data) {
                 Ok(_) => {
                     if !detected_cmds.contains(&cmd) {
-                        detected_cmds.push(cmd).map_err(|_| {
-                            crate::error::ErrorKind::Buffer(crate::error::BufferError::Overflow)
-                        })?;
+                        // Cannot overflow or truncate: the list is sized by
+                        // INIT_SEQUENCE_LEN and holds each command at most once.
+                        detected_cmds.push(cmd).ok();
                     }
-                    core::fmt::Write::write_str(writer, "  Command ").ok();
-                    crate::compat::util::write_bytes_hex_fmt(writer, &[cmd]).ok();
-                    core::fmt::Write::write_str(writer, " responded.\r\n").ok();
+                    dvcdbg_log!(writer, "  Command {cmd:02X} responded.\r\n");
                 }
                 Err(e) => {
                     let error_kind = e.to_compat(Some(addr));
                     if error_kind == crate::error::ErrorKind::I2c(crate::error::I2cError::Nack) {
-                        core::fmt::Write::write_str(writer, "  Command ").ok();
-                        crate::compat::util::write_bytes_hex_fmt(writer, &[cmd]).ok();
-                        core::fmt::Write::write_str(writer, " no response (NACK).\r\n").ok();
+                        dvcdbg_log!(writer, "  Command {cmd:02X} no response (NACK).\r\n");
                         continue;
                     }
-                    core::fmt::Write::write_str(writer, "  Write failed for ").ok();
-                    crate::compat::util::write_bytes_hex_fmt(writer, &[cmd]).ok();
-                    core::fmt::Write::write_str(writer, " at ").ok();
-                    crate::compat::util::write_bytes_hex_fmt(writer, &[addr]).ok();
-                    write!(writer, ": {error_kind}.\r\n").ok();
+                    dvcdbg_log!(
+                        writer,
+                        "  Write failed for {cmd:02X} at {addr:02X}: {error_kind}.\r\n"
+                    );
                     last_error = Some(error_kind);
                 }
             }
@@ -175,11 +870,9 @@ where
         .collect();
 
     fn log_commands<W: core::fmt::Write>(writer: &mut W, label: &str, cmds: &[u8]) {
-        core::fmt::Write::write_str(writer, label).ok();
-        core::fmt::Write::write_str(writer, "\r\n").ok();
+        dvcdbg_log!(writer, "{label}\r\n");
         for &b in cmds {
-            core::fmt::Write::write_str(writer, " ").ok();
-            crate::compat::util::write_bytes_hex_fmt(writer, &[b]).ok();
+            dvcdbg_log!(writer, " {b:02X}");
         }
     }
 
@@ -194,3 +887,85 @@ where
         Ok(detected_cmds)
     }
 }
+
+/// Idle level of the two I2C lines, as read by [`diagnose_bus_lines`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BusLineStatus {
+    /// Both lines released (high): the bus is idle.
+    Idle,
+    /// SDA held low, usually a target stuck mid-transaction.
+    SdaLow,
+    /// SCL held low, usually a target stretching the clock or a short.
+    SclLow,
+    /// Both lines low, usually missing pull-ups or an unpowered bus.
+    BothLow,
+}
+
+impl BusLineStatus {
+    /// The hardware fault this status points to, or `None` for an idle bus.
+    pub fn to_error(self) -> Option<crate::error::ErrorKind> {
+        use crate::error::{ErrorKind, HardwareError};
+        match self {
+            BusLineStatus::Idle => None,
+            BusLineStatus::SdaLow => Some(ErrorKind::Hardware(HardwareError::Peripheral)),
+            BusLineStatus::SclLow => Some(ErrorKind::Hardware(HardwareError::ShortCircuit)),
+            BusLineStatus::BothLow => Some(ErrorKind::Hardware(HardwareError::Power)),
+        }
+    }
+}
+
+/// Reads the idle level of SDA and SCL to tell which line, if any, is stuck low.
+///
+/// Run it with the pins configured as inputs (the I2C peripheral released)
+/// before any transaction, so a wedged bus is explained rather than showing
+/// up as a scan that finds nothing. A pin read error maps to `ErrorKind::Other`.
+pub fn diagnose_bus_lines<SDA, SCL>(
+    sda: &mut SDA,
+    scl: &mut SCL,
+) -> Result<BusLineStatus, crate::error::ErrorKind>
+where
+    SDA: crate::compat::InputPinCompat,
+    SCL: crate::compat::InputPinCompat,
+{
+    let sda_low = sda.is_low().map_err(|_| crate::error::ErrorKind::Other)?;
+    let scl_low = scl.is_low().map_err(|_| crate::error::ErrorKind::Other)?;
+    Ok(match (sda_low, scl_low) {
+        (false, false) => BusLineStatus::Idle,
+        (true, false) => BusLineStatus::SdaLow,
+        (false, true) => BusLineStatus::SclLow,
+        (true, true) => BusLineStatus::BothLow,
+    })
+}
+
+/// Pulses a device's hardware reset line, then waits for it to boot.
+///
+/// Drives `reset_pin` to its active level (low if `active_low`) for
+/// `pulse_us`, releases it, and waits `boot_us` before returning. Call it
+/// before [`scan_i2c`]: a device held in reset simply does not acknowledge,
+/// which is easily mistaken for a wiring fault. A pin error maps to
+/// `ErrorKind::Other`.
+pub fn hardware_reset<P, D>(
+    reset_pin: &mut P,
+    active_low: bool,
+    pulse_us: u32,
+    boot_us: u32,
+    delay: &mut D,
+) -> Result<(), crate::error::ErrorKind>
+where
+    P: crate::compat::OutputPinCompat,
+    D: crate::compat::DelayCompat,
+{
+    let mut drive = |level_high: bool| {
+        if level_high {
+            reset_pin.set_high()
+        } else {
+            reset_pin.set_low()
+        }
+        .map_err(|_| crate::error::ErrorKind::Other)
+    };
+    drive(!active_low)?;
+    delay.delay_us(pulse_us);
+    drive(active_low)?;
+    delay.delay_us(boot_us);
+    Ok(())
+}
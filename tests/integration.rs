@@ -53,6 +53,11 @@ impl I2cCompat for DummyI2c {
     }
 }
 
+struct DummyDelay;
+impl DelayCompat for DummyDelay {
+    fn delay_us(&mut self, _us: u32) {}
+}
+
 // -----------------------------
 // Integration test
 // -----------------------------
@@ -81,3 +86,2456 @@ fn test_full_stack() {
     write_bin!(&mut serial, &[0x00, 0xFF]);
     write_hex!(&mut serial, &[0xAA, 0xBB]);
 }
+
+#[test]
+fn test_explore_result_eq_ignores_unused_tail() {
+    use dvcdbg::explore::explorer::ExploreResult;
+
+    let mut a = ExploreResult {
+        found_addrs: [0u8; 128],
+        found_addrs_len: 1,
+        permutations_tested: 3,
+        aborted: false,
+        truncated: false,
+    };
+    let mut b = ExploreResult {
+        found_addrs: [0xFFu8; 128],
+        found_addrs_len: 1,
+        permutations_tested: 3,
+        aborted: false,
+        truncated: false,
+    };
+    a.found_addrs[0] = 0x3C;
+    b.found_addrs[0] = 0x3C;
+    assert_eq!(a, b);
+
+    b.found_addrs_len = 2;
+    assert_ne!(a, b);
+}
+
+/// Records every write so tests can inspect what went over the bus.
+#[derive(Default)]
+struct RecordingI2c {
+    writes: Vec<(u8, Vec<u8>)>,
+}
+
+impl I2cCompat for RecordingI2c {
+    type Error = core::convert::Infallible;
+
+    fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+        self.writes.push((addr, bytes.to_vec()));
+        Ok(())
+    }
+    fn read(&mut self, _addr: u8, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn write_read(
+        &mut self,
+        _addr: u8,
+        _bytes: &[u8],
+        _buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn probe(&mut self, _addr: u8) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+    fn is_nack(&self, _error: &Self::Error) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_prefix_executor_oversized_command() {
+    use dvcdbg::explore::explorer::{CmdExecutor, PrefixExecutor};
+
+    let mut i2c = RecordingI2c::default();
+    let mut serial = DummySerial;
+    let cmd = [0xA1, 0xA2, 0xA3, 0xA4, 0xA5];
+
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    let err = executor
+        .exec(&mut i2c, 0x3C, &cmd, &mut serial)
+        .err()
+        .unwrap();
+    assert!(
+        err == ExecutorError::CommandTooLarge {
+            required: 6,
+            available: 4
+        }
+    );
+    assert!(i2c.writes.is_empty());
+
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]).with_chunking(true);
+    assert!(executor.exec(&mut i2c, 0x3C, &cmd, &mut serial).is_ok());
+    assert_eq!(
+        i2c.writes,
+        vec![
+            (0x3C, vec![0x00, 0xA1, 0xA2, 0xA3]),
+            (0x3C, vec![0x00, 0xA4, 0xA5]),
+        ]
+    );
+}
+
+#[cfg(not(feature = "no-log"))]
+#[test]
+fn test_runner_log_captured_by_string_writer() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, PrefixExecutor};
+    use dvcdbg::explore::runner::run_on_bus;
+
+    static NODES: [CmdNode; 2] = [CmdNode::new(&[0xAE], &[]), CmdNode::new(&[0xAF], &[0])];
+    let explorer = Explorer::<2, 1>::new(&NODES);
+    let mut i2c = OracleI2c::<_, 1, 16>::new(&[0x3C], |_: u8, _: &[u8], _: &[u8]| true);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    let mut log = StringWriter::<1024>::new();
+
+    let result =
+        run_on_bus::<_, _, _, _, 2, 4, 1>(&explorer, &mut i2c, &mut log, &mut executor, 0x00);
+    assert!(result.is_ok());
+    assert_eq!(
+        log.as_str(),
+        concat!(
+            "[exprore] Attempting to get 1 init seq ...\r\n",
+            "Scanning I2C bus with a 00 ...\r\n",
+            "Found device @ 3C\r\n",
+            "[explorer] Obtained one topological sort. Executing on 3C...\r\n",
+            "I2C WRITE @3C:\n00 AE \n",
+            "[E] OK 0\r\n",
+            "I2C WRITE @3C:\n00 AF \n",
+            "[E] OK 1\r\n",
+            "[explorer] Single sequence execution complete for 3C.\r\n",
+        )
+    );
+}
+
+#[test]
+fn test_runner_reports_oversized_command_index() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, PrefixExecutor};
+    use dvcdbg::explore::runner::run_on_bus;
+
+    static NODES: [CmdNode; 2] = [
+        CmdNode::new(&[0xAE], &[]),
+        CmdNode::new(&[0xA1, 0xA2, 0xA3, 0xA4], &[0]),
+    ];
+    let explorer = Explorer::<2, 1>::new(&NODES);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    let result = run_on_bus::<_, _, _, _, 2, 4, 1>(
+        &explorer,
+        &mut RecordingI2c::default(),
+        &mut DummySerial,
+        &mut executor,
+        0x00,
+    );
+    let err = result.err().unwrap();
+    assert!(
+        err == ExplorerError::CommandTooLarge {
+            index: Some(1),
+            required: 5,
+            available: 4
+        }
+    );
+    assert_eq!(
+        format!("{err}"),
+        "CommandTooLarge: command 1 needs 5 bytes, buffer holds 4"
+    );
+
+    let unindexed: ExplorerError = ExecutorError::CommandTooLarge {
+        required: 5,
+        available: 4,
+    }
+    .into();
+    assert!(unindexed.with_command_index(1) == err);
+}
+
+#[test]
+fn test_prefix_executor_caches_absent_probe() {
+    use dvcdbg::explore::explorer::{CmdExecutor, PrefixExecutor};
+
+    /// A bus where nothing acknowledges a probe.
+    #[derive(Default)]
+    struct EmptyBus {
+        probes: usize,
+    }
+    impl I2cCompat for EmptyBus {
+        type Error = core::convert::Infallible;
+
+        fn write(&mut self, _addr: u8, _bytes: &[u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn read(&mut self, _addr: u8, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn write_read(
+            &mut self,
+            _addr: u8,
+            _bytes: &[u8],
+            _buffer: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn probe(&mut self, _addr: u8) -> Result<bool, Self::Error> {
+            self.probes += 1;
+            Ok(false)
+        }
+        fn is_nack(&self, _error: &Self::Error) -> bool {
+            false
+        }
+    }
+
+    let mut i2c = EmptyBus::default();
+    let mut executor = PrefixExecutor::<1, 4>::new(0x00, &[0xAE]);
+    for _ in 0..3 {
+        executor
+            .exec(&mut i2c, 0x3C, &[0xAF], &mut DummySerial)
+            .ok();
+    }
+    assert_eq!(i2c.probes, 1);
+
+    executor.reset_init_state();
+    executor
+        .exec(&mut i2c, 0x3C, &[0xAF], &mut DummySerial)
+        .ok();
+    assert_eq!(i2c.probes, 2);
+}
+
+#[test]
+fn test_prefix_executor_max_attempts() {
+    use core::cell::Cell;
+    use dvcdbg::explore::explorer::{CmdExecutor, PrefixExecutor};
+
+    // The device NACKs the first two frames it sees.
+    let attempts = Cell::new(0);
+    let flaky = |_: u8, _: &[u8], _: &[u8]| {
+        attempts.set(attempts.get() + 1);
+        attempts.get() > 2
+    };
+
+    let mut i2c = OracleI2c::<_, 1, 8>::new(&[0x3C], flaky);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]).with_retry_delay(0);
+    assert!(
+        executor
+            .exec(&mut i2c, 0x3C, &[0xAF], &mut DummySerial)
+            .is_err()
+    );
+    assert_eq!(attempts.get(), 2);
+
+    attempts.set(0);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[])
+        .with_max_attempts(3)
+        .with_retry_delay(0);
+    assert!(
+        executor
+            .exec(&mut i2c, 0x3C, &[0xAF], &mut DummySerial)
+            .is_ok()
+    );
+    assert_eq!(attempts.get(), 3);
+}
+
+#[test]
+fn test_prefix_executor_custom_delay() {
+    use dvcdbg::explore::explorer::{CmdExecutor, PrefixExecutor};
+
+    /// Records every requested pause.
+    struct RecordingDelay<'a>(&'a core::cell::RefCell<Vec<u32>>);
+    impl DelayCompat for RecordingDelay<'_> {
+        fn delay_us(&mut self, us: u32) {
+            self.0.borrow_mut().push(us);
+        }
+    }
+
+    // The device NACKs its first frame.
+    let frames = core::cell::Cell::new(0);
+    let reject_first = |_: u8, _: &[u8], _: &[u8]| {
+        frames.set(frames.get() + 1);
+        frames.get() > 1
+    };
+    let mut i2c = OracleI2c::<_, 1, 8>::new(&[0x3C], reject_first);
+    let pauses = core::cell::RefCell::new(Vec::new());
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[])
+        .with_retry_delay(50)
+        .with_post_write_delay(7)
+        .with_delay(RecordingDelay(&pauses));
+    assert!(
+        executor
+            .exec(&mut i2c, 0x3C, &[0xAF], &mut DummySerial)
+            .is_ok()
+    );
+    assert_eq!(*pauses.borrow(), vec![50, 7]);
+}
+
+#[test]
+fn test_scan_i2c_bitmap_marks_acks() {
+    let reject = |_: u8, _: &[u8], _: &[u8]| false;
+
+    let mut i2c = OracleI2c::<_, 2, 4>::new(&[0x3C, 0x48], reject);
+    let Ok(bitmap) = scan_i2c_bitmap(&mut i2c, &mut DummySerial) else {
+        panic!("bitmap scan failed");
+    };
+    assert_eq!(bitmap.iter_set().collect::<Vec<_>>(), vec![0x3C, 0x48]);
+
+    // Every address NACKs: an empty map, not an error.
+    let mut i2c = OracleI2c::<_, 1, 4>::new(&[], reject);
+    let Ok(bitmap) = scan_i2c_bitmap(&mut i2c, &mut DummySerial) else {
+        panic!("bitmap scan failed");
+    };
+    assert!(bitmap.is_empty());
+}
+
+#[test]
+fn test_read_register_and_burst() {
+    /// Registers hold their own index, so a burst read returns consecutive values.
+    struct RegisterFile;
+    impl I2cCompat for RegisterFile {
+        type Error = core::convert::Infallible;
+
+        fn write(&mut self, _addr: u8, _bytes: &[u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn read(&mut self, _addr: u8, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn write_read(
+            &mut self,
+            _addr: u8,
+            bytes: &[u8],
+            buffer: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            for (i, b) in buffer.iter_mut().enumerate() {
+                *b = bytes[0] + i as u8;
+            }
+            Ok(())
+        }
+        fn probe(&mut self, _addr: u8) -> Result<bool, Self::Error> {
+            Ok(true)
+        }
+        fn is_nack(&self, _error: &Self::Error) -> bool {
+            false
+        }
+    }
+
+    let mut writer = StringWriter::<64>::new();
+    assert!(read_register(&mut RegisterFile, &mut writer, 0x3C, 0x0F) == Ok(0x0F));
+    let mut buf = [0u8; 3];
+    assert!(read_registers(&mut RegisterFile, &mut writer, 0x3C, 0x20, &mut buf).is_ok());
+    assert_eq!(buf, [0x20, 0x21, 0x22]);
+    #[cfg(not(feature = "no-log"))]
+    assert_eq!(
+        writer.as_str(),
+        "[reg] @3C:0F = 0F\r\n[reg] @3C:20 = 20 21 22\r\n"
+    );
+}
+
+#[test]
+fn test_write_scan_csv() {
+    let mut writer = StringWriter::<2048>::new();
+    write_scan_csv(&[0x03, 0x3C], &mut writer).unwrap();
+    let lines: Vec<&str> = writer.as_str().lines().collect();
+    assert_eq!(lines.len(), 1 + (0x77 - 0x03 + 1));
+    assert_eq!(lines[..3], ["address,present", "0x03,yes", "0x04,no"]);
+    assert!(lines.contains(&"0x3C,yes"));
+    assert_eq!(lines.last(), Some(&"0x77,no"));
+}
+
+#[test]
+fn test_scan_i2c_timed_reports_idle_gaps() {
+    use std::cell::Cell;
+
+    let ticks = Cell::new(0u32);
+    let time = || {
+        ticks.set(ticks.get() + 1);
+        ticks.get()
+    };
+    let mut i2c = DummyI2c;
+    let mut serial = DummySerial;
+
+    let Ok((found, stats)) = scan_i2c_timed(&mut i2c, &mut serial, 0x00, &time) else {
+        panic!("timed scan failed");
+    };
+    assert_eq!(found.len(), 0x77 - 0x03 + 1);
+    assert_eq!(stats.samples as usize, found.len() - 1);
+    assert_eq!((stats.min, stats.max, stats.avg()), (1, 1, 1));
+}
+
+#[test]
+fn test_probe_with_custom_classifier() {
+    #[derive(Debug, PartialEq)]
+    enum FakeError {
+        Absent,
+        Fault,
+    }
+
+    struct FaultyI2c;
+    impl I2cCompat for FaultyI2c {
+        type Error = FakeError;
+
+        fn write(&mut self, addr: u8, _bytes: &[u8]) -> Result<(), Self::Error> {
+            match addr {
+                0x3C => Ok(()),
+                0x3D => Err(FakeError::Absent),
+                _ => Err(FakeError::Fault),
+            }
+        }
+        fn read(&mut self, _addr: u8, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn write_read(
+            &mut self,
+            _addr: u8,
+            _bytes: &[u8],
+            _buffer: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn probe(&mut self, _addr: u8) -> Result<bool, Self::Error> {
+            Ok(false)
+        }
+        fn is_nack(&self, _error: &Self::Error) -> bool {
+            false
+        }
+    }
+
+    let mut i2c = FaultyI2c;
+    let absent = |e: &FakeError| *e == FakeError::Absent;
+    assert_eq!(i2c.probe_with(0x3C, absent), Ok(true));
+    assert_eq!(i2c.probe_with(0x3D, absent), Ok(false));
+    assert_eq!(i2c.probe_with(0x3E, absent), Err(FakeError::Fault));
+}
+
+#[test]
+fn test_tracing_executor_capture_and_replay() {
+    use dvcdbg::explore::explorer::{CmdExecutor, PrefixExecutor};
+    use dvcdbg::explore::trace::{TracingExecutor, replay_trace};
+
+    let mut i2c = RecordingI2c::default();
+    let mut serial = DummySerial;
+    let mut tracer =
+        TracingExecutor::<_, 8, 4>::new(PrefixExecutor::<0, 4>::new(0x00, &[]).with_chunking(true));
+
+    assert!(tracer.exec(&mut i2c, 0x3C, &[0xAE], &mut serial).is_ok());
+    assert!(
+        tracer
+            .exec(&mut i2c, 0x3C, &[0xA1, 0xA2, 0xA3, 0xA4], &mut serial)
+            .is_ok()
+    );
+    assert_eq!(tracer.trace().len(), 3);
+    assert_eq!(tracer.dropped(), 0);
+    assert_eq!(
+        tracer.trace()[1].bytes.as_slice(),
+        &[0x00, 0xA1, 0xA2, 0xA3]
+    );
+
+    let mut target = RecordingI2c::default();
+    let replayed = replay_trace(&mut target, &mut serial, tracer.trace(), 0x3D);
+    assert!(replayed == Ok(3));
+    assert_eq!(
+        target.writes,
+        vec![
+            (0x3D, vec![0x00, 0xAE]),
+            (0x3D, vec![0x00, 0xA1, 0xA2, 0xA3]),
+            (0x3D, vec![0x00, 0xA4]),
+        ]
+    );
+}
+
+#[cfg(not(feature = "no-log"))]
+#[test]
+fn test_string_writer_captures_log() {
+    use dvcdbg::explore::trace::{TraceRecord, replay_trace};
+
+    let trace = [TraceRecord::<4> {
+        addr: 0x3C,
+        bytes: heapless::Vec::from_slice(&[0x00, 0xAF]).unwrap(),
+        ok: true,
+    }];
+    let mut writer = StringWriter::<256>::new();
+    assert!(replay_trace(&mut DummyI2c, &mut writer, &trace, 0x3C) == Ok(1));
+    assert!(writer.as_str().contains("[replay] 0: 00 AF OK"));
+    assert!(writer.as_str().ends_with("[replay] Done, 1 writes\r\n"));
+}
+
+#[test]
+fn test_all_topological_orders() {
+    use dvcdbg::compat::util::BitFlags;
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+
+    static NODES: [CmdNode; 3] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[0]),
+    ];
+    let explorer = Explorer::<3, 1>::new(&NODES);
+
+    let failed = BitFlags::new();
+    let mut iter = explorer.all_topological_iter(&failed).ok().unwrap();
+    let orders: Vec<Vec<u8>> = iter.by_ref().map(|o| o.to_vec()).collect();
+    assert_eq!(orders, vec![vec![0, 1, 2], vec![0, 2, 1], vec![1, 0, 2]]);
+    assert!(!iter.is_cycle_detected());
+    // 3 orders x 3 commands x 2 devices x 10 cycles
+    assert_eq!(explorer.estimate_runtime(10, 2), Some(180));
+
+    let mut failed = BitFlags::new();
+    failed.set(1).unwrap();
+    let orders: Vec<Vec<u8>> = explorer
+        .all_topological_iter(&failed)
+        .ok()
+        .unwrap()
+        .map(|o| o.to_vec())
+        .collect();
+    assert_eq!(orders, vec![vec![0, 2]]);
+}
+
+#[test]
+fn test_topological_iter_fifo_follows_declaration_order() {
+    use dvcdbg::compat::util::BitFlags;
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, TopologicalIter};
+
+    static NODES: [CmdNode; 4] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[]),
+        CmdNode::new(&[0xA3], &[0]),
+    ];
+    let explorer = Explorer::<4, 1>::new(&NODES);
+    let failed = BitFlags::new();
+
+    let lifo: Vec<usize> = explorer.topological_iter(&failed).ok().unwrap().collect();
+    assert_eq!(lifo, vec![2, 1, 0, 3]);
+    let fifo: Vec<usize> = TopologicalIter::new_fifo(&explorer, &failed)
+        .ok()
+        .unwrap()
+        .collect();
+    assert_eq!(fifo, vec![0, 1, 2, 3]);
+}
+
+#[test]
+fn test_count_topological_sorts_matches_enumeration() {
+    use dvcdbg::compat::util::BitFlags;
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+
+    static NODES: [CmdNode; 6] = [
+        CmdNode::new(&[0xA0], &[]).first(),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[1]),
+        CmdNode::new(&[0xA3], &[]),
+        CmdNode::new(&[0xA4], &[]),
+        CmdNode::new(&[0xAF], &[]).last(),
+    ];
+    let explorer = Explorer::<6, 1>::new(&NODES);
+
+    let mut failed = BitFlags::new();
+    for _ in 0..2 {
+        let enumerated = explorer.all_topological_iter(&failed).ok().unwrap().count();
+        assert!(explorer.count_topological_sorts(&failed) == Ok(enumerated));
+        failed.set(3).unwrap();
+    }
+    // 4 free nodes with one edge: 4! / 2
+    assert!(explorer.count_topological_sorts(&BitFlags::new()) == Ok(12));
+
+    // A chain has a single order.
+    let chain = demo_graph::EXPLORER.count_topological_sorts(&BitFlags::new());
+    assert!(chain == Ok(1));
+
+    static CYCLE: [CmdNode; 2] = [CmdNode::new(&[0xA0], &[1]), CmdNode::new(&[0xA1], &[0])];
+    let cyclic = Explorer::<2, 2>::new(&CYCLE).count_topological_sorts(&BitFlags::new());
+    assert!(cyclic == Err(ExplorerError::DependencyCycle));
+}
+
+#[test]
+fn test_count_topological_sorts_free_nodes_without_enumerating() {
+    use dvcdbg::compat::util::BitFlags;
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+    use std::time::{Duration, Instant};
+
+    static FREE: [CmdNode; 12] = [CmdNode::new(&[0xA0], &[]); 12];
+    let explorer = Explorer::<12, 0>::new(&FREE);
+
+    let start = Instant::now();
+    let count = explorer.count_topological_sorts_with_memo::<4096>(&BitFlags::new());
+    assert!(count == Ok(479_001_600)); // 12!
+    assert!(start.elapsed() < Duration::from_secs(1));
+
+    // The default memo cannot hold the 2^12 reachable sets.
+    let too_big = explorer.count_topological_sorts(&BitFlags::new());
+    assert!(too_big == Err(ExplorerError::BufferOverflow));
+}
+
+#[test]
+fn test_node_depths_and_leveled_graph() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+
+    static NODES: [CmdNode; 4] = [
+        CmdNode::new(&[0xAE], &[]),
+        CmdNode::new(&[0xD5, 0x80], &[]),
+        CmdNode::new(&[0xA8, 0x3F], &[0]),
+        CmdNode::new(&[0xAF], &[1, 2]),
+    ];
+    let explorer = Explorer::<4, 3>::new(&NODES);
+
+    assert_eq!(
+        explorer.node_depths().ok().unwrap().as_slice(),
+        &[0, 0, 1, 2]
+    );
+
+    let mut writer = StringWriter::<128>::new();
+    assert!(explorer.write_graph_leveled(&mut writer).is_ok());
+    assert_eq!(
+        writer.as_str(),
+        "L0: #0[AE] #1[D5 80]\r\nL1: #2[A8 3F]\r\nL2: #3[AF]\r\n"
+    );
+}
+
+#[test]
+fn test_write_dot_emits_edges_even_for_cycles() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+
+    static CYCLE: [CmdNode; 3] = [
+        CmdNode::new(&[0xAE], &[]),
+        CmdNode::new(&[0xD5, 0x80], &[2]),
+        CmdNode::new(&[0xAF], &[1]),
+    ];
+    let mut writer = StringWriter::<256>::new();
+    assert!(Explorer::<3, 2>::new(&CYCLE).write_dot(&mut writer).is_ok());
+    assert_eq!(
+        writer.as_str(),
+        "digraph {\r\n  0 [label=\"#0: AE\"];\r\n  1 [label=\"#1: D5 80\"];\r\n  \
+         2 [label=\"#2: AF\"];\r\n  2 -> 1;\r\n  1 -> 2;\r\n}\r\n"
+    );
+}
+
+#[test]
+fn test_scan_mux_tags_channels() {
+    struct MuxI2c {
+        select: u8,
+    }
+    impl I2cCompat for MuxI2c {
+        type Error = core::convert::Infallible;
+
+        fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+            if addr == 0x70 {
+                self.select = bytes[0];
+            }
+            Ok(())
+        }
+        fn read(&mut self, _addr: u8, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn write_read(
+            &mut self,
+            _addr: u8,
+            _bytes: &[u8],
+            _buffer: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn probe(&mut self, addr: u8) -> Result<bool, Self::Error> {
+            Ok(matches!(
+                (self.select, addr),
+                (_, 0x70) | (0b01, 0x3C) | (0b10, 0x3C) | (0b10, 0x48)
+            ))
+        }
+        fn is_nack(&self, _error: &Self::Error) -> bool {
+            false
+        }
+    }
+
+    let mut i2c = MuxI2c { select: 0 };
+    let mux = ScanMux::new(0x70, 3);
+    let Ok(found) = mux.scan::<_, _, 8>(&mut i2c, &mut DummySerial, 0x00) else {
+        panic!("mux scan failed");
+    };
+    assert_eq!(found.as_slice(), &[(0, 0x3C), (1, 0x3C), (1, 0x48)]);
+    assert_eq!(i2c.select, 0);
+}
+
+#[test]
+fn test_mutable_explorer_disabled_node_releases_dependents() {
+    use dvcdbg::compat::util::BitFlags;
+    use dvcdbg::explore::explorer::{CmdNode, MutableExplorer, NodeSet};
+
+    static NODES: [CmdNode; 3] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[0]),
+    ];
+    let Ok(mut explorer) = MutableExplorer::<3, 1>::new(&NODES) else {
+        panic!("node set too large");
+    };
+    assert!(explorer.disable_node(0).is_ok());
+    assert!(!explorer.is_enabled(0));
+    assert!(explorer.disable_node(3).is_err());
+
+    let failed = BitFlags::new();
+    let orders: Vec<Vec<u8>> = explorer
+        .all_topological_iter(&failed)
+        .ok()
+        .unwrap()
+        .map(|o| o.to_vec())
+        .collect();
+    assert_eq!(orders, vec![vec![1, 2], vec![2, 1]]);
+
+    assert!(explorer.enable_node(0).is_ok());
+    let mut iter = explorer.topological_iter(&failed).ok().unwrap();
+    assert_eq!(iter.by_ref().count(), 3);
+    assert!(!iter.is_cycle_detected());
+}
+
+#[test]
+fn test_runtime_explorer_matches_static_explorer() {
+    use dvcdbg::compat::util::BitFlags;
+    use dvcdbg::explore::explorer::{DepIndex, NodeSet, OwnedCmdNode, RuntimeExplorer};
+    use dvcdbg::explore::program::InitProgram;
+
+    // demo_graph's nodes, as if read from a config at runtime.
+    let config: [(&[u8], &[DepIndex]); 3] =
+        [(&[0xAE], &[]), (&[0xD5, 0x80], &[0]), (&[0xAF], &[1])];
+    let nodes: Vec<OwnedCmdNode<2, 1>> = config
+        .iter()
+        .map(|(bytes, deps)| OwnedCmdNode::new(bytes, deps).ok().unwrap())
+        .collect();
+    assert!(OwnedCmdNode::<1, 1>::new(&[0xD5, 0x80], &[]).is_err());
+
+    let Ok(explorer) = RuntimeExplorer::<3, 2>::new(&nodes) else {
+        panic!("node set too large");
+    };
+    assert!(RuntimeExplorer::<2, 2>::new(&nodes).is_err());
+
+    let failed = BitFlags::new();
+    let runtime: Vec<usize> = explorer.topological_iter(&failed).ok().unwrap().collect();
+    let fixed: Vec<usize> = demo_graph::EXPLORER
+        .topological_iter(&failed)
+        .ok()
+        .unwrap()
+        .collect();
+    assert_eq!(runtime, fixed);
+
+    let Ok(program) = InitProgram::<3, 3>::first_sort(&explorer, 0x3C, 0x00) else {
+        panic!("program failed");
+    };
+    assert_eq!(program.frames(), &[&[0xAE][..], &[0xD5, 0x80], &[0xAF]]);
+}
+
+#[test]
+fn test_run_with_events_reports_typed_events() {
+    use dvcdbg::explore::event::Event;
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, PrefixExecutor};
+    use dvcdbg::explore::runner::run_with_events;
+
+    static NODES: [CmdNode; 2] = [CmdNode::new(&[0xAE], &[]), CmdNode::new(&[0xAF], &[0])];
+    let explorer = Explorer::<2, 1>::new(&NODES);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+
+    let mut found = 0;
+    let mut ok = Vec::new();
+    let mut done = None;
+    let mut sink = |e: Event<'_>| match e {
+        Event::Found(_) => found += 1,
+        Event::CmdOk { addr, idx } => ok.push((addr, idx)),
+        Event::Done(addr) => done = Some(addr),
+        _ => {}
+    };
+    assert!(run_with_events(&explorer, &mut DummyI2c, &mut executor, &mut sink, 0x00).is_ok());
+
+    assert_eq!(found, 0x77 - 0x03 + 1);
+    assert_eq!(ok, vec![(0x03, 0), (0x03, 1)]);
+    assert_eq!(done, Some(0x03));
+}
+
+#[test]
+fn test_scan_i2c_range_limits_and_validates() {
+    let found = scan_i2c_range(&mut DummyI2c, &mut DummySerial, 0x00, 0x40, 0x43);
+    assert_eq!(found.ok().unwrap().as_slice(), &[0x40, 0x41, 0x42, 0x43]);
+
+    let reversed = scan_i2c_range(&mut DummyI2c, &mut DummySerial, 0x00, 0x50, 0x40);
+    assert!(reversed == Err(ErrorKind::InvalidConfig));
+    let too_high = scan_i2c_range(&mut DummyI2c, &mut DummySerial, 0x00, 0x70, 0x80);
+    assert!(too_high == Err(ErrorKind::InvalidConfig));
+}
+
+#[test]
+fn test_bitflags_first_set_and_clear() {
+    use dvcdbg::compat::util::BitFlags;
+
+    let mut flags = BitFlags::new();
+    assert_eq!(flags.first_set(), None);
+    assert_eq!(flags.first_clear(0), Some(0));
+
+    for idx in 0..20 {
+        flags.set(idx).unwrap();
+    }
+    flags.set(0x77).unwrap();
+    assert_eq!(flags.first_set(), Some(0));
+    assert_eq!(flags.first_clear(0), Some(20));
+    assert_eq!(flags.first_clear(21), Some(21));
+    assert_eq!(flags.first_clear(0x77), Some(0x78));
+    assert_eq!(flags.first_clear(128), None);
+
+    flags.clear_all();
+    flags.set(0x50).unwrap();
+    assert_eq!(flags.first_set(), Some(0x50));
+    for idx in 0..128 {
+        flags.set(idx).unwrap();
+    }
+    assert_eq!(flags.first_clear(0), None);
+}
+
+#[test]
+fn test_bitflags_n_sized_by_bytes() {
+    use dvcdbg::compat::util::{BitFlags, BitFlagsN};
+    use dvcdbg::error::BitFlagsError;
+
+    let mut nodes = BitFlagsN::<2>::new();
+    assert_eq!(BitFlagsN::<2>::N_BITS, 16);
+    assert_eq!(BitFlags::N_BITS, 128);
+    assert_eq!(core::mem::size_of::<BitFlagsN<2>>(), 2);
+
+    nodes.set(15).unwrap();
+    assert_eq!(nodes.first_set(), Some(15));
+    nodes.set(3).unwrap();
+    assert_eq!(nodes.count_ones(), 2);
+    assert_eq!(nodes.iter_set().collect::<Vec<_>>(), vec![3, 15]);
+    assert_eq!(
+        nodes.set(16),
+        Err(BitFlagsError::IndexOutOfBounds { idx: 16, max: 15 })
+    );
+}
+
+#[test]
+fn test_bitflags_snapshot_compares_by_value() {
+    use dvcdbg::compat::util::BitFlags;
+
+    let mut failed = BitFlags::new();
+    failed.set(3).unwrap();
+    // `Copy`: plain assignment takes a snapshot.
+    let snapshot = failed;
+    assert_eq!(snapshot, failed);
+
+    failed.set(4).unwrap();
+    assert_ne!(snapshot, failed);
+    assert_eq!(snapshot.iter_set().collect::<Vec<_>>(), vec![3]);
+}
+
+#[test]
+fn test_bitflags_operators_across_byte_boundaries() {
+    use dvcdbg::compat::util::BitFlags;
+
+    let low = BitFlags::from_u128(0x0180); // bits 7 and 8: either side of a byte edge
+    let high = BitFlags::from_u128(1 << 127);
+    assert_eq!(low.iter_set().collect::<Vec<_>>(), vec![7, 8]);
+    assert_eq!(high.first_set(), Some(127));
+
+    let both = low | high;
+    assert_eq!(both.to_u128(), 0x0180 | 1 << 127);
+    assert_eq!((both & low).to_u128(), 0x0180);
+    assert!((low & high).is_empty());
+    assert_eq!((!low).to_u128(), !0x0180u128);
+    assert_eq!((!low).count_ones(), 126);
+
+    let mut acc = BitFlags::new();
+    acc |= low;
+    acc &= BitFlags::from_u128(0x0100);
+    assert_eq!(acc.iter_set().collect::<Vec<_>>(), vec![8]);
+}
+
+#[cfg(not(feature = "no-log"))]
+#[test]
+fn test_scan_diff_reports_changes() {
+    use dvcdbg::compat::util::BitFlags;
+
+    let mut before = BitFlags::new();
+    before.set(0x3C).unwrap();
+    before.set(0x48).unwrap();
+    let mut after = BitFlags::new();
+    after.set(0x3C).unwrap();
+    after.set(0x50).unwrap();
+
+    let mut writer = StringWriter::<64>::new();
+    let (appeared, disappeared) = write_scan_diff(&mut writer, &before, &after);
+    assert_eq!(appeared.iter_set().collect::<Vec<_>>(), vec![0x50]);
+    assert_eq!(disappeared.iter_set().collect::<Vec<_>>(), vec![0x48]);
+    assert_eq!(writer.as_str(), "0x50 appeared\r\n0x48 disappeared\r\n");
+}
+
+#[cfg(feature = "ehal_async")]
+#[test]
+fn test_one_topological_explorer_async() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+    use dvcdbg::explore::runner::one_topological_explorer_async;
+    use embedded_hal_async::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+    struct AsyncI2c {
+        writes: Vec<Vec<u8>>,
+        fail_first: bool,
+    }
+    impl ErrorType for AsyncI2c {
+        type Error = ErrorKind;
+    }
+    impl I2c for AsyncI2c {
+        async fn transaction(
+            &mut self,
+            addr: u8,
+            ops: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            if addr != 0x3C {
+                return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address));
+            }
+            for op in ops {
+                if let Operation::Write(bytes) = op
+                    && !bytes.is_empty()
+                {
+                    if core::mem::take(&mut self.fail_first) {
+                        return Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Data));
+                    }
+                    self.writes.push(bytes.to_vec());
+                }
+            }
+            Ok(())
+        }
+    }
+
+    struct Delay(Vec<u32>);
+    impl embedded_hal_async::delay::DelayNs for Delay {
+        async fn delay_ns(&mut self, ns: u32) {
+            self.0.push(ns);
+        }
+    }
+
+    static NODES: [CmdNode; 2] = [
+        CmdNode::new(&[0xAE], &[]).with_delay_us(300),
+        CmdNode::new(&[0xAF], &[0]),
+    ];
+    let explorer = Explorer::<2, 1>::new(&NODES);
+    // The first command is NACKed once and succeeds on its retry.
+    let mut i2c = AsyncI2c {
+        writes: Vec::new(),
+        fail_first: true,
+    };
+    let mut delay = Delay(Vec::new());
+
+    let mut serial = StringWriter::<512>::new();
+    let result = {
+        let fut = one_topological_explorer_async::<_, _, _, _, 2, 4, 1>(
+            &explorer,
+            &mut i2c,
+            &mut serial,
+            &mut delay,
+            0x00,
+        );
+        let mut fut = core::pin::pin!(fut);
+        let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+        let core::task::Poll::Ready(result) = fut.as_mut().poll(&mut cx) else {
+            panic!("mock bus never pends");
+        };
+        result
+    };
+
+    assert!(result.is_ok());
+    assert_eq!(i2c.writes, vec![vec![0x00, 0xAE], vec![0x00, 0xAF]]);
+    // Retry pause, post-write pause and node delay, as a PrefixExecutor would.
+    assert_eq!(delay.0, [1_000_000, 1_000_000, 300_000, 1_000_000]);
+    #[cfg(not(feature = "no-log"))]
+    assert!(
+        serial
+            .as_str()
+            .contains("I2C WRITE @3C:\n00 AE \n[I2C retry error] I2c: Nack at 0x3C")
+    );
+}
+
+#[cfg(feature = "ehal_async")]
+#[test]
+fn test_scan_i2c_async() {
+    use embedded_hal_async::i2c::{ErrorKind, ErrorType, I2c, NoAcknowledgeSource, Operation};
+
+    struct TwoDevices;
+    impl ErrorType for TwoDevices {
+        type Error = ErrorKind;
+    }
+    impl I2c for TwoDevices {
+        async fn transaction(
+            &mut self,
+            addr: u8,
+            _ops: &mut [Operation<'_>],
+        ) -> Result<(), Self::Error> {
+            match addr {
+                0x3C | 0x48 => Ok(()),
+                _ => Err(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+            }
+        }
+    }
+
+    let mut i2c = TwoDevices;
+    let mut serial = DummySerial;
+    let fut = scan_i2c_async(&mut i2c, &mut serial, 0x00);
+    let mut fut = core::pin::pin!(fut);
+    let mut cx = core::task::Context::from_waker(core::task::Waker::noop());
+    let core::task::Poll::Ready(found) = fut.as_mut().poll(&mut cx) else {
+        panic!("mock bus never pends");
+    };
+    assert_eq!(found.ok().unwrap().as_slice(), &[0x3C, 0x48]);
+}
+
+#[cfg(feature = "ehal_1_0")]
+#[test]
+fn test_scan_i2c_probe_propagates_bus_errors() {
+    use embedded_hal_1::i2c::{ErrorKind as HalErrorKind, NoAcknowledgeSource};
+
+    /// 0x3C acks, 0x3D NACKs with an error, and `fault` (if any) is a bus error.
+    struct ProbeBus {
+        fault: Option<u8>,
+    }
+    impl I2cCompat for ProbeBus {
+        type Error = HalErrorKind;
+
+        fn write(&mut self, _addr: u8, _bytes: &[u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn read(&mut self, _addr: u8, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn write_read(
+            &mut self,
+            _addr: u8,
+            _bytes: &[u8],
+            _buffer: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn probe(&mut self, addr: u8) -> Result<bool, Self::Error> {
+            match addr {
+                0x3C => Ok(true),
+                0x3D => Err(HalErrorKind::NoAcknowledge(NoAcknowledgeSource::Address)),
+                _ if Some(addr) == self.fault => Err(HalErrorKind::Bus),
+                _ => Ok(false),
+            }
+        }
+        fn is_nack(&self, error: &Self::Error) -> bool {
+            matches!(error, HalErrorKind::NoAcknowledge(_))
+        }
+    }
+
+    let found = scan_i2c_probe(&mut ProbeBus { fault: None }, &mut DummySerial);
+    assert_eq!(found.ok().unwrap().as_slice(), &[0x3C]);
+
+    let mut faulty = ProbeBus { fault: Some(0x50) };
+    let strict = scan_i2c_probe(&mut faulty, &mut DummySerial);
+    assert!(strict == Err(ErrorKind::I2c(I2cError::Bus)));
+    // The lenient scan still reports the device it found.
+    let lenient = scan_i2c(&mut faulty, &mut DummySerial, 0x00);
+    assert_eq!(lenient.ok().unwrap().as_slice(), &[0x3C]);
+
+    let mut writer = StringWriter::<64>::new();
+    let report = scan_i2c_report(&mut faulty, &mut writer);
+    assert_eq!(report.found.as_slice(), &[0x3C]);
+    assert_eq!(report.addresses_probed, 0x77 - 0x03 + 1);
+    assert_eq!(
+        (report.nacks, report.bus_errors),
+        (report.addresses_probed - 2, 1)
+    );
+    #[cfg(not(feature = "no-log"))]
+    assert_eq!(
+        writer.as_str(),
+        "[scan] 1 found / 117 probed / 115 NACK / 1 bus errors\r\n"
+    );
+}
+
+#[cfg(feature = "ehal_1_0")]
+#[test]
+fn test_explore_with_accepts_benign_nack() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, PrefixExecutor};
+    use embedded_hal_1::i2c::{ErrorKind as HalErrorKind, NoAcknowledgeSource};
+
+    /// Acks only at 0x3C, and NACKs the final byte of the display-on command.
+    struct QuirkyI2c;
+    impl I2cCompat for QuirkyI2c {
+        type Error = HalErrorKind;
+
+        fn write(&mut self, _addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+            if bytes.last() == Some(&0xAF) {
+                Err(HalErrorKind::NoAcknowledge(NoAcknowledgeSource::Data))
+            } else {
+                Ok(())
+            }
+        }
+        fn read(&mut self, _addr: u8, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn write_read(
+            &mut self,
+            _addr: u8,
+            _bytes: &[u8],
+            _buffer: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn probe(&mut self, addr: u8) -> Result<bool, Self::Error> {
+            Ok(addr == 0x3C)
+        }
+        fn is_nack(&self, error: &Self::Error) -> bool {
+            matches!(error, HalErrorKind::NoAcknowledge(_))
+        }
+    }
+
+    static NODES: [CmdNode; 2] = [CmdNode::new(&[0xAE], &[]), CmdNode::new(&[0xAF], &[0])];
+    let explorer = Explorer::<2, 1>::new(&NODES);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+
+    let Ok(strict) = explorer.explore(&mut QuirkyI2c, &mut executor, &mut DummySerial) else {
+        panic!("explore failed");
+    };
+    assert!(strict.found().is_empty());
+    assert_eq!(strict.permutations_tested, 1);
+
+    let benign_nack =
+        |e: &ExecutorError| *e == ExecutorError::I2cError(ErrorKind::I2c(I2cError::Nack));
+    let Ok(lenient) =
+        explorer.explore_with(&mut QuirkyI2c, &mut executor, &mut DummySerial, benign_nack)
+    else {
+        panic!("explore_with failed");
+    };
+    assert_eq!(lenient.found(), &[0x3C]);
+}
+
+#[test]
+fn test_scan_i2c_yielding_calls_back_periodically() {
+    let mut yields = 0;
+    let found = scan_i2c_yielding(&mut DummyI2c, &mut DummySerial, 0x00, 16, || yields += 1);
+    assert!(found.is_ok());
+    // 0x03..=0x77 is 117 addresses
+    assert_eq!(yields, 117 / 16);
+}
+
+#[test]
+fn test_explore_with_custom_strategy_skips_invalid_orders() {
+    use dvcdbg::explore::explorer::{
+        CmdNode, ExploreOptions, Explorer, PermutationStrategy, PrefixExecutor,
+    };
+
+    struct Fixed(Vec<heapless::Vec<u8, 2>>);
+    impl PermutationStrategy<2> for Fixed {
+        fn next_order(&mut self) -> Option<heapless::Vec<u8, 2>> {
+            self.0.pop()
+        }
+    }
+
+    static NODES: [CmdNode; 2] = [CmdNode::new(&[0xAE], &[]), CmdNode::new(&[0xAF], &[0])];
+    let explorer = Explorer::<2, 1>::new(&NODES);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    // Popped from the back: the invalid order comes first.
+    let mut strategy = Fixed(vec![
+        heapless::Vec::from_slice(&[0, 1]).unwrap(),
+        heapless::Vec::from_slice(&[1, 0]).unwrap(),
+    ]);
+
+    let Ok(result) = explorer.explore_with_strategy(
+        &mut DummyI2c,
+        &mut executor,
+        &mut DummySerial,
+        &mut strategy,
+        |_| false,
+        &ExploreOptions::new(),
+    ) else {
+        panic!("explore failed");
+    };
+    assert_eq!(result.permutations_tested, 1);
+    assert_eq!(result.found().len(), 0x77 - 0x03 + 1);
+
+    let mut strategy = Fixed(vec![heapless::Vec::from_slice(&[0, 1]).unwrap()]);
+    let budgeted = explorer.explore_with_strategy(
+        &mut DummyI2c,
+        &mut executor,
+        &mut DummySerial,
+        &mut strategy,
+        |_| false,
+        &ExploreOptions::new().with_max_writes(3),
+    );
+    assert!(budgeted.err() == Some(ExplorerError::WriteBudgetExhausted));
+}
+
+#[test]
+fn test_explore_with_capacity_bounds_found_addrs() {
+    use dvcdbg::explore::explorer::{
+        CmdNode, ExploreOptions, ExploreResult, Explorer, PrefixExecutor,
+    };
+
+    static NODES: [CmdNode; 1] = [CmdNode::new(&[0xAE], &[])];
+    let explorer = Explorer::<1, 0>::new(&NODES);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    let accept_all = |_: u8, _: &[u8], _: &[u8]| true;
+
+    let mut i2c = OracleI2c::<_, 2, 16>::new(&[0x3C, 0x3D], accept_all);
+    let mut strategy = explorer
+        .all_topological_iter(&Default::default())
+        .ok()
+        .unwrap();
+    let result: Result<ExploreResult<2>, _> = explorer.explore_with_capacity(
+        &mut i2c,
+        &mut executor,
+        &mut DummySerial,
+        &mut strategy,
+        |_| false,
+        &ExploreOptions::new(),
+    );
+    let Ok(result) = result else {
+        panic!("explore failed");
+    };
+    assert_eq!(result.found(), &[0x3C, 0x3D]);
+
+    let mut i2c = OracleI2c::<_, 2, 16>::new(&[0x3C, 0x3D], accept_all);
+    let mut strategy = explorer
+        .all_topological_iter(&Default::default())
+        .ok()
+        .unwrap();
+    let result: Result<ExploreResult<1>, _> = explorer.explore_with_capacity(
+        &mut i2c,
+        &mut executor,
+        &mut DummySerial,
+        &mut strategy,
+        |_| false,
+        &ExploreOptions::new(),
+    );
+    assert!(result.err() == Some(ExplorerError::BufferOverflow));
+}
+
+#[test]
+fn test_abort_flag_stops_exploration() {
+    use core::sync::atomic::AtomicBool;
+    use dvcdbg::explore::explorer::{CmdNode, ExploreOptions, Explorer, PrefixExecutor};
+    use dvcdbg::explore::runner::pruning_explorer_with_abort;
+
+    static NODES: [CmdNode; 2] = [CmdNode::new(&[0xAE], &[]), CmdNode::new(&[0xAF], &[])];
+    let explorer = Explorer::<2, 0>::new(&NODES);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    let stop = AtomicBool::new(true);
+
+    let Ok(result) = explorer.explore_with_strategy(
+        &mut DummyI2c,
+        &mut executor,
+        &mut DummySerial,
+        &mut explorer
+            .all_topological_iter(&Default::default())
+            .ok()
+            .unwrap(),
+        |_| false,
+        &ExploreOptions::new().with_abort(&stop),
+    ) else {
+        panic!("explore failed");
+    };
+    assert!(result.aborted);
+    assert_eq!(result.permutations_tested, 0);
+
+    let res = pruning_explorer_with_abort::<_, _, _, 2, 8, 0>(
+        &explorer,
+        &mut DummyI2c,
+        &mut DummySerial,
+        0x00,
+        1,
+        &stop,
+    );
+    assert!(res == Err(ExplorerError::Aborted));
+}
+
+#[cfg(not(feature = "no-log"))]
+#[test]
+fn test_pruning_explorer_with_retries_tries_other_orders() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+    use dvcdbg::explore::runner::{pruning_explorer, pruning_explorer_with_retries};
+
+    static NODES: [CmdNode; 2] = [CmdNode::new(&[0xA0], &[]), CmdNode::new(&[0xA1], &[])];
+    // The first order tried is [1, 0]; only the batch in [0, 1] is accepted.
+    let rule = |_: u8, _: &[u8], frame: &[u8]| frame.len() != 3 || frame == [0x00, 0xA0, 0xA1];
+    let explorer = Explorer::<2, 0>::new(&NODES);
+
+    let mut log = String::new();
+    let mut i2c = OracleI2c::<_, 1, 16>::new(&[0x3C], rule);
+    assert!(pruning_explorer::<_, _, _, 2, 8, 0>(&explorer, &mut i2c, &mut log, 0x00).is_ok());
+    assert!(log.contains("[W] Pruning nodes after failures @ 3C"));
+
+    let mut log = String::new();
+    let mut i2c = OracleI2c::<_, 1, 16>::new(&[0x3C], rule);
+    let res =
+        pruning_explorer_with_retries::<_, _, _, 2, 8, 0>(&explorer, &mut i2c, &mut log, 0x00, 2);
+    assert!(res.is_ok());
+    assert!(log.contains("[I] Retry 3C with order 2/2"));
+    assert!(!log.contains("Pruning"));
+}
+
+#[test]
+fn test_max_permutations_truncates_explore() {
+    use dvcdbg::explore::explorer::{CmdNode, ExploreOptions, Explorer, PrefixExecutor};
+
+    static NODES: [CmdNode; 3] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[]),
+    ];
+    // Only the third order tried, [1, 0, 2], works.
+    let rule = |_: u8, history: &[u8], frame: &[u8]| {
+        [0xA1, 0xA0, 0xA2].get(history.len() / 2) == frame.get(1)
+    };
+    let explorer = Explorer::<3, 0>::new(&NODES);
+
+    for (max, found, truncated) in [(2, &[][..], true), (5, &[0x3C][..], false)] {
+        let mut i2c = OracleI2c::<_, 1, 16>::new(&[0x3C], rule);
+        let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+        let Ok(result) = explorer.explore_with_strategy(
+            &mut i2c,
+            &mut executor,
+            &mut DummySerial,
+            &mut explorer
+                .all_topological_iter(&Default::default())
+                .ok()
+                .unwrap(),
+            |_| false,
+            &ExploreOptions::new().with_max_permutations(max),
+        ) else {
+            panic!("explore failed");
+        };
+        assert_eq!(result.found(), found);
+        assert_eq!(result.truncated, truncated);
+        // Stops at the cap, or early once every device is solved.
+        assert_eq!(result.permutations_tested, max.min(3));
+    }
+}
+
+#[test]
+fn test_failed_prefix_cache_skips_doomed_orders() {
+    use core::cell::Cell;
+    use dvcdbg::explore::explorer::{
+        CmdNode, ExploreOptions, ExploreResult, Explorer, FailedPrefixCache, PrefixExecutor,
+    };
+
+    static NODES: [CmdNode; 3] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[]),
+    ];
+    let explorer = Explorer::<3, 0>::new(&NODES);
+    // The device rejects A0 as its first command.
+    let writes = Cell::new(0);
+    let rule = |_: u8, history: &[u8], frame: &[u8]| {
+        writes.set(writes.get() + 1);
+        !(history.is_empty() && frame[1] == 0xA0)
+    };
+
+    let mut i2c = OracleI2c::<_, 1, 16>::new(&[0x3C], rule);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    let mut cache = FailedPrefixCache::<8>::new();
+    let result: Result<ExploreResult, _> = explorer.explore_with_cache(
+        &mut i2c,
+        &mut executor,
+        &mut DummySerial,
+        &mut explorer
+            .all_topological_iter(&Default::default())
+            .ok()
+            .unwrap(),
+        |_| false,
+        &ExploreOptions::new(),
+        &mut cache,
+    );
+    let Ok(result) = result else {
+        panic!("explore failed");
+    };
+    assert_eq!(result.found(), &[0x3C]);
+    assert_eq!(result.permutations_tested, 3);
+    // [0, 1, 2] fails on its first write (sent twice with the retry),
+    // [0, 2, 1] is skipped without a write, [1, 0, 2] passes.
+    assert_eq!(writes.get(), 2 + 3);
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn test_run_per_address_uses_each_devices_graph() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, PrefixExecutor};
+    use dvcdbg::explore::runner::run_per_address;
+
+    static DISPLAY: [CmdNode; 1] = [CmdNode::new(&[0xAF], &[])];
+    static SENSOR: [CmdNode; 1] = [CmdNode::new(&[0x01], &[])];
+    let display = Explorer::<1, 0>::new(&DISPLAY);
+    let sensor = Explorer::<1, 0>::new(&SENSOR);
+
+    let mut i2c = RecordingI2c::default();
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    let Ok(failed) = run_per_address(
+        &[(0x3C, &display), (0x48, &sensor)],
+        &mut i2c,
+        &mut DummySerial,
+        &mut executor,
+        0x00,
+    ) else {
+        panic!("scan failed");
+    };
+
+    assert!(failed.is_empty());
+    assert_eq!(
+        i2c.writes,
+        vec![(0x3C, vec![0x00, 0xAF]), (0x48, vec![0x00, 0x01])]
+    );
+}
+
+#[test]
+fn test_write_scan_binary_frames_addresses() {
+    let mut buf = [0u8; 8];
+    let mut out = &mut buf[..];
+    assert!(write_scan_binary(&[0x3C, 0x48], &mut out).is_ok());
+    assert_eq!(
+        &buf[..4],
+        &[dvcdbg::scanner::SCAN_PACKET_MAGIC, 2, 0x3C, 0x48]
+    );
+}
+
+#[cfg(not(feature = "no-log"))]
+#[test]
+fn test_one_topological_explorer_timed_logs_ticks() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+    use dvcdbg::explore::runner::one_topological_explorer_timed;
+    use std::cell::Cell;
+
+    static NODES: [CmdNode; 2] = [CmdNode::new(&[0xAE], &[]), CmdNode::new(&[0xAF], &[0])];
+    let explorer = Explorer::<2, 1>::new(&NODES);
+
+    let ticks = Cell::new(0u32);
+    let time = || {
+        ticks.set(ticks.get() + 10);
+        ticks.get()
+    };
+    let mut writer = StringWriter::<8192>::new();
+    let res = one_topological_explorer_timed::<_, _, _, _, 2, 8, 1>(
+        &explorer,
+        &mut DummyI2c,
+        &mut writer,
+        0x00,
+        &time,
+    );
+    assert!(res.is_ok());
+    assert!(writer.as_str().contains("cmd 0: OK (10 ticks)"));
+    assert!(writer.as_str().contains("cmd 1: OK (10 ticks)"));
+    assert!(writer.as_str().contains("Sequence took 50 ticks"));
+}
+
+#[cfg(not(feature = "no-log"))]
+#[test]
+fn test_risky_ctrl_byte_warning() {
+    use dvcdbg::scanner::RISKY_CTRL_BYTES;
+
+    let mut writer = StringWriter::<256>::new();
+    assert!(!warn_if_risky_ctrl_byte(
+        &mut writer,
+        0x00,
+        &RISKY_CTRL_BYTES
+    ));
+    assert!(writer.as_str().is_empty());
+    assert!(warn_if_risky_ctrl_byte(
+        &mut writer,
+        0xAE,
+        &RISKY_CTRL_BYTES
+    ));
+    assert!(writer.as_str().contains("ctrl byte AE"));
+}
+
+dvcdbg::nodes!(
+    mod demo_graph,
+    prefix = 0x00,
+    [[0xAE], [0xD5, 0x80] @ [0], [0xAF] @ [1]]
+);
+
+#[test]
+fn test_nodes_mod_exposes_buffer_sizes() {
+    assert_eq!(demo_graph::N, 3);
+    assert_eq!(demo_graph::MAX_DEPS, 2);
+    assert_eq!(demo_graph::CMD_BUFFER_SIZE, 3);
+    assert_eq!(demo_graph::BATCH_BUFFER_SIZE, 5);
+
+    let (_, executor) = nodes!(prefix = 0x00, [[0xAE], [0xD5, 0x80] @ [0]]);
+    let _: dvcdbg::explore::explorer::PrefixExecutor<0, 3> = executor;
+
+    assert!(get_one_sort!(graph = demo_graph, &mut DummyI2c, &mut DummySerial).is_ok());
+    assert!(pruning_sort!(graph = demo_graph, &mut DummyI2c, &mut DummySerial).is_ok());
+}
+
+dvcdbg::nodes!(
+    mod delayed_graph,
+    prefix = 0x00,
+    [[0x01] delay = 500, [0xAE] @ [0], [0xAF] @ [1] delay = 20 => Last]
+);
+
+#[test]
+fn test_nodes_delay_paused_after_command() {
+    use dvcdbg::explore::runner::run_on_bus;
+    use std::cell::RefCell;
+
+    assert_eq!(delayed_graph::NODES[0].delay_us, 500);
+    assert_eq!(delayed_graph::NODES[1].delay_us, 0);
+    assert!(matches!(
+        delayed_graph::NODES[2].placement,
+        dvcdbg::explore::explorer::Placement::Last
+    ));
+
+    let pauses = RefCell::new(Vec::new());
+    struct Delay<'a>(&'a RefCell<Vec<u32>>);
+    impl DelayCompat for Delay<'_> {
+        fn delay_us(&mut self, us: u32) {
+            if us > 0 {
+                self.0.borrow_mut().push(us);
+            }
+        }
+    }
+
+    let mut executor = delayed_graph::executor(Delay(&pauses)).with_post_write_delay(0);
+    let result = run_on_bus::<
+        _,
+        _,
+        _,
+        _,
+        { delayed_graph::N },
+        { delayed_graph::CMD_BUFFER_SIZE },
+        { delayed_graph::MAX_DEPS },
+    >(
+        &delayed_graph::EXPLORER,
+        &mut DummyI2c,
+        &mut DummySerial,
+        &mut executor,
+        delayed_graph::PREFIX,
+    );
+    assert!(result.is_ok());
+    assert_eq!(*pauses.borrow(), [500, 20]);
+}
+
+#[test]
+fn test_run_on_all_devices_with_tracing_executor() {
+    use dvcdbg::explore::runner::run_on_all_devices;
+    use dvcdbg::explore::trace::TracingExecutor;
+
+    let mut tracer = TracingExecutor::<_, 512, 4>::new(demo_graph::executor(DummyDelay));
+    let failed = run_on_all_devices::<
+        _,
+        _,
+        _,
+        _,
+        { demo_graph::N },
+        { demo_graph::CMD_BUFFER_SIZE },
+        { demo_graph::MAX_DEPS },
+    >(
+        &demo_graph::EXPLORER,
+        &mut DummyI2c,
+        &mut DummySerial,
+        &mut tracer,
+        demo_graph::PREFIX,
+    );
+    assert!(failed.is_ok_and(|f| f.is_empty()));
+    // 3 commands on each of the 117 probed addresses
+    assert_eq!(tracer.trace().len(), 3 * 117);
+    assert_eq!(tracer.trace()[1].bytes.as_slice(), &[0x00, 0xD5, 0x80]);
+}
+
+#[cfg(not(feature = "no-log"))]
+#[test]
+fn test_explore_progress_log() {
+    use dvcdbg::explore::explorer::{CmdNode, ExploreOptions, Explorer, PrefixExecutor};
+
+    let mut executor = demo_graph::executor(DummyDelay);
+    let mut writer = StringWriter::<16384>::new();
+    let result = demo_graph::EXPLORER.explore_with_strategy(
+        &mut DummyI2c,
+        &mut executor,
+        &mut writer,
+        &mut demo_graph::EXPLORER
+            .all_topological_iter(&Default::default())
+            .ok()
+            .unwrap(),
+        |_| false,
+        &ExploreOptions::new().with_progress_every(1),
+    );
+    assert!(result.is_ok());
+    assert!(
+        writer
+            .as_str()
+            .contains("[explore] Progress: 1/1 orders (100%), 117/117 devices solved")
+    );
+
+    // Three independent commands: 6 orders, none accepted by the device.
+    static FREE: [CmdNode; 3] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[]),
+    ];
+    let explorer = Explorer::<3, 0>::new(&FREE);
+    let mut i2c = OracleI2c::<_, 1, 4>::new(&[0x3C], |_, _: &[u8], _: &[u8]| false);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    writer.clear();
+    let result = explorer.explore_with_strategy(
+        &mut i2c,
+        &mut executor,
+        &mut writer,
+        &mut explorer
+            .all_topological_iter(&Default::default())
+            .ok()
+            .unwrap(),
+        |_| false,
+        &ExploreOptions::new().with_progress_every(2),
+    );
+    assert!(result.is_ok());
+    let log = writer.as_str();
+    assert!(log.contains("[explore] Progress: 2/6 orders (33%), 0/1 devices solved"));
+    assert!(log.contains("[explore] Progress: 6/6 orders (100%), 0/1 devices solved"));
+}
+
+#[test]
+fn test_node_placement_first_and_last() {
+    use dvcdbg::compat::util::BitFlags;
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+
+    static NODES: [CmdNode; 4] = [
+        CmdNode::new(&[0xAF], &[]).last(),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[]),
+        CmdNode::new(&[0xAE], &[]).first(),
+    ];
+    let explorer = Explorer::<4, 0>::new(&NODES);
+    let failed = BitFlags::new();
+
+    let Ok(iter) = explorer.topological_iter(&failed) else {
+        panic!("iter failed");
+    };
+    let order: Vec<usize> = iter.collect();
+    assert_eq!(order.first(), Some(&3));
+    assert_eq!(order.last(), Some(&0));
+
+    let orders: Vec<_> = explorer
+        .all_topological_iter(&failed)
+        .ok()
+        .unwrap()
+        .collect();
+    assert_eq!(orders.len(), 2);
+    assert!(orders.iter().all(|o| o[0] == 3 && o[3] == 0));
+    assert!(!explorer.is_valid_order(&[1, 3, 2, 0]));
+
+    let (graph, _) = nodes!(prefix = 0x00, [[0xAF] => Last, [0xA1], [0xAE] => First]);
+    assert!(graph.is_valid_order(&[2, 1, 0]));
+    assert!(!graph.is_valid_order(&[1, 2, 0]));
+}
+
+#[cfg(feature = "ehal_1_0")]
+#[test]
+fn test_diagnose_bus_lines() {
+    use dvcdbg::error::{ErrorKind, HardwareError};
+
+    struct Level(bool);
+    impl embedded_hal_1::digital::ErrorType for Level {
+        type Error = core::convert::Infallible;
+    }
+    impl embedded_hal_1::digital::InputPin for Level {
+        fn is_high(&mut self) -> Result<bool, Self::Error> {
+            Ok(self.0)
+        }
+        fn is_low(&mut self) -> Result<bool, Self::Error> {
+            Ok(!self.0)
+        }
+    }
+
+    let status = diagnose_bus_lines(&mut Level(true), &mut Level(true));
+    assert!(status == Ok(BusLineStatus::Idle));
+    let status = diagnose_bus_lines(&mut Level(false), &mut Level(true));
+    assert!(status == Ok(BusLineStatus::SdaLow));
+    assert!(
+        BusLineStatus::SdaLow.to_error() == Some(ErrorKind::Hardware(HardwareError::Peripheral))
+    );
+    let status = diagnose_bus_lines(&mut Level(true), &mut Level(false));
+    assert!(status == Ok(BusLineStatus::SclLow));
+    let status = diagnose_bus_lines(&mut Level(false), &mut Level(false));
+    assert!(status == Ok(BusLineStatus::BothLow));
+}
+
+#[cfg(feature = "ehal_1_0")]
+#[test]
+fn test_hardware_reset_pulses_line() {
+    use std::cell::RefCell;
+
+    let events = RefCell::new(Vec::new());
+    struct Pin<'a>(&'a RefCell<Vec<String>>);
+    impl embedded_hal_1::digital::ErrorType for Pin<'_> {
+        type Error = core::convert::Infallible;
+    }
+    impl embedded_hal_1::digital::OutputPin for Pin<'_> {
+        fn set_low(&mut self) -> Result<(), Self::Error> {
+            self.0.borrow_mut().push("low".into());
+            Ok(())
+        }
+        fn set_high(&mut self) -> Result<(), Self::Error> {
+            self.0.borrow_mut().push("high".into());
+            Ok(())
+        }
+    }
+    struct Delay<'a>(&'a RefCell<Vec<String>>);
+    impl embedded_hal_1::delay::DelayNs for Delay<'_> {
+        fn delay_ns(&mut self, ns: u32) {
+            self.0.borrow_mut().push(format!("{}us", ns / 1000));
+        }
+    }
+
+    let mut pin = Pin(&events);
+    let mut delay = Delay(&events);
+    assert!(hardware_reset(&mut pin, true, 10, 2000, &mut delay).is_ok());
+    assert_eq!(*events.borrow(), ["low", "10us", "high", "2000us"]);
+
+    events.borrow_mut().clear();
+    assert!(hardware_reset(&mut pin, false, 5, 100, &mut delay).is_ok());
+    assert_eq!(*events.borrow(), ["high", "5us", "low", "100us"]);
+}
+
+#[cfg(feature = "ehal_1_0")]
+#[test]
+fn test_prefix_executor_uses_hal_delay() {
+    use dvcdbg::explore::explorer::{CmdExecutor, PrefixExecutor};
+    use std::cell::RefCell;
+
+    let pauses = RefCell::new(Vec::new());
+    struct Delay<'a>(&'a RefCell<Vec<u32>>);
+    impl embedded_hal_1::delay::DelayNs for Delay<'_> {
+        fn delay_ns(&mut self, ns: u32) {
+            self.0.borrow_mut().push(ns / 1000);
+        }
+    }
+
+    let mut i2c = RecordingI2c::default();
+    let mut executor = PrefixExecutor::<1, 4>::new(0x00, &[0xAE])
+        .with_post_write_delay(100)
+        .with_delay(Delay(&pauses));
+    assert!(
+        executor
+            .exec(&mut i2c, 0x3C, &[0xAF], &mut DummySerial)
+            .is_ok()
+    );
+    // One pause after the init sequence, one after the command.
+    assert_eq!(*pauses.borrow(), [100, 100]);
+    assert_eq!(i2c.writes.len(), 2);
+}
+
+#[test]
+fn test_verifying_executor_checks_status() {
+    use dvcdbg::explore::explorer::{CmdExecutor, PrefixExecutor, VerifyingExecutor};
+
+    /// Status bit 6 mirrors the last display on/off command (AF/AE).
+    #[derive(Default)]
+    struct StatusBus {
+        status: u8,
+    }
+    impl I2cCompat for StatusBus {
+        type Error = core::convert::Infallible;
+
+        fn write(&mut self, _addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
+            match bytes.last() {
+                Some(0xAF) => self.status = 0x40,
+                Some(0xAE) => self.status = 0x00,
+                _ => {}
+            }
+            Ok(())
+        }
+        fn read(&mut self, _addr: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
+            buffer.fill(self.status);
+            Ok(())
+        }
+        fn write_read(
+            &mut self,
+            _addr: u8,
+            _bytes: &[u8],
+            buffer: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            buffer.fill(self.status);
+            Ok(())
+        }
+        fn probe(&mut self, _addr: u8) -> Result<bool, Self::Error> {
+            Ok(true)
+        }
+        fn is_nack(&self, _error: &Self::Error) -> bool {
+            false
+        }
+    }
+
+    let mut i2c = StatusBus::default();
+    let mut executor =
+        VerifyingExecutor::new(PrefixExecutor::<0, 4>::new(0x00, &[]), 0x00, 0x40, 0x40);
+    assert!(
+        executor
+            .exec(&mut i2c, 0x3C, &[0xAF], &mut DummySerial)
+            .is_ok()
+    );
+    assert!(
+        executor
+            .exec(&mut i2c, 0x3C, &[0xAE], &mut DummySerial)
+            .err()
+            == Some(ExecutorError::ExecFailed)
+    );
+}
+
+#[test]
+fn test_error_kind_code_round_trip() {
+    assert_eq!(ErrorKind::I2c(I2cError::Nack).as_code(), 0x0200);
+    assert_eq!(ErrorKind::Buffer(BufferError::Underflow).as_code(), 0x0701);
+    assert_eq!(ErrorKind::Other.as_code(), 0x0003);
+    assert!(ErrorKind::from_code(0x0000).is_none());
+    assert!(ErrorKind::from_code(0x0203).is_none());
+
+    // Every assigned code decodes to a variant that encodes back to it.
+    let mut assigned = 0;
+    for code in 0..=u16::MAX {
+        if let Some(kind) = ErrorKind::from_code(code) {
+            assert_eq!(kind.as_code(), code);
+            assigned += 1;
+        }
+    }
+    assert_eq!(assigned, 20);
+}
+
+#[cfg(feature = "ehal_1_0")]
+#[test]
+fn test_to_compat_at_keeps_plain_kind() {
+    use dvcdbg::compat::HalErrorExt;
+    use embedded_hal_1::i2c::{ErrorKind as HalErrorKind, NoAcknowledgeSource};
+
+    let hal = HalErrorKind::NoAcknowledge(NoAcknowledgeSource::Address);
+    let err = hal.to_compat_at(0x3C);
+    assert!(err.kind == hal.to_compat(Some(0x3C)));
+    assert!(ErrorKind::from(err) == ErrorKind::I2c(I2cError::Nack));
+    assert_eq!(err.addr, 0x3C);
+    assert_eq!(format!("{err}"), "I2c: Nack at 0x3C");
+}
+
+#[test]
+fn test_explorer_error_inner_kind() {
+    let nack = ErrorKind::I2c(I2cError::Nack);
+    assert!(ExplorerError::ExecutionFailed(nack).inner_kind() == Some(nack));
+    assert!(ExplorerError::DeviceNotFound(ErrorKind::Other).inner_kind() == Some(ErrorKind::Other));
+    assert!(ExplorerError::DependencyCycle.inner_kind().is_none());
+}
+
+/// Reports a one-byte status register: the number of writes seen when
+/// `counting`, a constant otherwise.
+struct StatusI2c {
+    writes: u8,
+    counting: bool,
+}
+
+impl I2cCompat for StatusI2c {
+    type Error = core::convert::Infallible;
+
+    fn write(&mut self, _addr: u8, _bytes: &[u8]) -> Result<(), Self::Error> {
+        self.writes += 1;
+        Ok(())
+    }
+    fn read(&mut self, _addr: u8, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn write_read(
+        &mut self,
+        _addr: u8,
+        _bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        buffer[0] = if self.counting { self.writes } else { 0x80 };
+        Ok(())
+    }
+    fn probe(&mut self, _addr: u8) -> Result<bool, Self::Error> {
+        Ok(true)
+    }
+    fn is_nack(&self, _error: &Self::Error) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_check_idempotent_compares_status_snapshots() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, PrefixExecutor};
+    use dvcdbg::explore::runner::check_idempotent;
+
+    static NODES: [CmdNode; 2] = [CmdNode::new(&[0xAE], &[]), CmdNode::new(&[0xAF], &[0])];
+    let explorer = Explorer::<2, 1>::new(&NODES);
+
+    for (counting, expected) in [(true, false), (false, true)] {
+        let mut i2c = StatusI2c {
+            writes: 0,
+            counting,
+        };
+        let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+        let res = check_idempotent::<_, _, _, _, 2, 4, 1, 1>(
+            &explorer,
+            &mut i2c,
+            &mut DummySerial,
+            &mut executor,
+            0x3C,
+            0x00,
+        );
+        assert!(res.ok() == Some(expected));
+        assert_eq!(i2c.writes, 4);
+    }
+}
+
+#[test]
+fn test_run_commands_collects_each_result() {
+    use dvcdbg::explore::runner::run_commands;
+
+    let mut i2c = RecordingI2c::default();
+    let cmds: [&[u8]; 3] = [&[0xAE], &[0xA1, 0xA2, 0xA3, 0xA4], &[0xAF]];
+    let results = run_commands::<_, _, 4, 4>(&mut i2c, &mut DummySerial, 0x3C, &cmds, 0x00);
+
+    assert_eq!(results.len(), 3);
+    assert!(results[0].is_ok() && results[2].is_ok());
+    assert!(matches!(
+        results[1],
+        Err(ExecutorError::CommandTooLarge { required: 5, .. })
+    ));
+    assert_eq!(
+        i2c.writes,
+        vec![(0x3C, vec![0x00, 0xAE]), (0x3C, vec![0x00, 0xAF])]
+    );
+}
+
+#[test]
+fn test_learn_dependencies_infers_precedence() {
+    use dvcdbg::explore::runner::learn_dependencies;
+
+    // A1 needs A0 first, A2 needs A1 first; A3 is free. Any rejected frame
+    // resets the device.
+    let rule = |_: u8, history: &[u8], frame: &[u8]| match frame[1] {
+        0xA1 => history.contains(&0xA0),
+        0xA2 => history.contains(&0xA1),
+        0xFF => false,
+        _ => true,
+    };
+    let mut i2c = OracleI2c::<_, 1, 16>::new(&[0x3C], rule);
+    // Listed out of order, so the identity permutation fails.
+    let cmds: [&[u8]; 4] = [&[0xA2], &[0xA0], &[0xA1], &[0xA3]];
+    let reset = |i2c: &mut OracleI2c<_, 1, 16>| {
+        i2c.write(0x3C, &[0x00, 0xFF]).ok();
+    };
+
+    let Ok(deps) = learn_dependencies::<_, _, _, 4, 2, 6>(
+        &mut i2c,
+        &mut DummySerial,
+        &cmds,
+        0x3C,
+        0x00,
+        24,
+        reset,
+    ) else {
+        panic!("learning failed");
+    };
+    // A0 (1) before A1 (2) before A2 (0); the implied 1 -> 0 is dropped.
+    assert_eq!(deps.as_slice(), &[(1, 2), (2, 0)]);
+
+    let no_luck = learn_dependencies::<_, _, _, 4, 2, 6>(
+        &mut i2c,
+        &mut DummySerial,
+        &cmds,
+        0x3C,
+        0x00,
+        1,
+        reset,
+    );
+    assert!(no_luck.err() == Some(ExplorerError::NoValidAddressesFound));
+}
+
+#[test]
+fn test_learn_dependencies_blames_only_the_swapped_command() {
+    use dvcdbg::explore::runner::learn_dependencies;
+
+    // X is free, A1 needs A0. Moving A1 in front of X must not drag it in
+    // front of A0 as well.
+    let rule = |_: u8, history: &[u8], frame: &[u8]| match frame[1] {
+        0xA1 => history.contains(&0xA0),
+        0xFF => false,
+        _ => true,
+    };
+    let mut i2c = OracleI2c::<_, 1, 16>::new(&[0x3C], rule);
+    let cmds: [&[u8]; 3] = [&[0x58], &[0xA0], &[0xA1]];
+    let reset = |i2c: &mut OracleI2c<_, 1, 16>| {
+        i2c.write(0x3C, &[0x00, 0xFF]).ok();
+    };
+
+    let deps = learn_dependencies::<_, _, _, 3, 2, 2>(
+        &mut i2c,
+        &mut DummySerial,
+        &cmds,
+        0x3C,
+        0x00,
+        1,
+        reset,
+    );
+    assert_eq!(deps.ok().unwrap().as_slice(), &[(1, 2)]);
+}
+
+#[test]
+fn test_learn_dependencies_chain_fits_reduced_capacity() {
+    use dvcdbg::explore::runner::learn_dependencies;
+
+    // A0 -> A1 -> A2 -> A3 -> A4: 10 ordered pairs, 4 direct edges.
+    let rule = |_: u8, history: &[u8], frame: &[u8]| match frame[1] {
+        0xFF => false,
+        0xA0 => true,
+        b => history.contains(&(b - 1)),
+    };
+    let mut i2c = OracleI2c::<_, 1, 16>::new(&[0x3C], rule);
+    let cmds: [&[u8]; 5] = [&[0xA0], &[0xA1], &[0xA2], &[0xA3], &[0xA4]];
+    let reset = |i2c: &mut OracleI2c<_, 1, 16>| {
+        i2c.write(0x3C, &[0x00, 0xFF]).ok();
+    };
+
+    let deps = learn_dependencies::<_, _, _, 5, 2, 4>(
+        &mut i2c,
+        &mut DummySerial,
+        &cmds,
+        0x3C,
+        0x00,
+        1,
+        reset,
+    );
+    assert_eq!(
+        deps.ok().unwrap().as_slice(),
+        &[(0, 1), (1, 2), (2, 3), (3, 4)]
+    );
+}
+
+#[test]
+fn test_runner_rejects_out_of_range_node_index() {
+    use dvcdbg::compat::util::BitFlags;
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, NodeSet, TopologicalIter};
+    use dvcdbg::explore::runner::one_topological_explorer;
+
+    static NODES: [CmdNode; 2] = [CmdNode::new(&[0xAE], &[]), CmdNode::new(&[0xAF], &[])];
+
+    /// Sorts over both nodes but only exposes the first one.
+    struct Truncated(Explorer<2, 0>);
+    impl NodeSet<2, 0> for Truncated {
+        fn nodes(&self) -> &[CmdNode<'_>] {
+            &NODES[..1]
+        }
+        fn topological_iter(
+            &self,
+            failed_nodes: &BitFlags,
+        ) -> Result<TopologicalIter<'_, 2, 0>, ExplorerError> {
+            NodeSet::topological_iter(&self.0, failed_nodes)
+        }
+    }
+
+    let explorer = Truncated(Explorer::new(&NODES));
+    let res = one_topological_explorer::<_, _, _, 2, 0, 4, 0>(
+        &explorer,
+        &mut DummyI2c,
+        &mut DummySerial,
+        0x00,
+    );
+    assert!(res == Err(ExplorerError::InvalidDependencyIndex));
+}
+
+#[test]
+fn test_check_dep_bounds_pinpoints_bad_dep() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+
+    static GOOD: [CmdNode; 2] = [CmdNode::new(&[0xAE], &[]), CmdNode::new(&[0xAF], &[0])];
+    const CHECKED: Explorer<2, 1> = Explorer::new(&GOOD);
+    const _: () = assert!(CHECKED.check_dep_bounds().is_ok());
+
+    static BAD: [CmdNode; 3] = [
+        CmdNode::new(&[0xAE], &[]),
+        CmdNode::new(&[0xA8], &[0, 7]),
+        CmdNode::new(&[0xAF], &[3]),
+    ];
+    let explorer = Explorer::<3, 3>::new(&BAD);
+    assert_eq!(explorer.check_dep_bounds(), Err((1, 7)));
+}
+
+#[test]
+fn test_unlocked_by_lists_next_choices() {
+    use dvcdbg::compat::util::BitFlags;
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+
+    // 0 -> 1, {0, 2} -> 3, 2 free.
+    static NODES: [CmdNode; 4] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[0]),
+        CmdNode::new(&[0xA2], &[]),
+        CmdNode::new(&[0xA3], &[0, 2]),
+    ];
+    let explorer = Explorer::<4, 3>::new(&NODES);
+
+    let mut done = BitFlags::new();
+    assert_eq!(explorer.unlocked_by(0, &done).as_slice(), &[1, 2]);
+    done.set(0).unwrap();
+    assert_eq!(explorer.unlocked_by(2, &done).as_slice(), &[1, 3]);
+}
+
+#[test]
+fn test_find_duplicate_commands() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+
+    static NODES: [CmdNode; 4] = [
+        CmdNode::new(&[0xAE], &[]),
+        CmdNode::new(&[0xD5, 0x80], &[]),
+        CmdNode::new(&[0xAE], &[]),
+        CmdNode::new(&[0xAE], &[1]),
+    ];
+    let explorer = Explorer::<4, 1>::new(&NODES);
+    assert_eq!(
+        explorer.find_duplicate_commands().as_slice(),
+        &[(0, 2), (0, 3)]
+    );
+}
+
+#[test]
+fn test_explore_discovers_order_required_by_oracle() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, PrefixExecutor};
+
+    static NODES: [CmdNode; 3] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[]),
+    ];
+    // 0x3C accepts the commands only as 0, 2, 1 (frames are prefix + command).
+    let rule = |_: u8, history: &[u8], frame: &[u8]| {
+        [0xA0, 0xA2, 0xA1].get(history.len() / 2) == frame.get(1)
+    };
+
+    let explorer = Explorer::<3, 0>::new(&NODES);
+    let mut i2c = OracleI2c::<_, 2, 16>::new(&[0x3C, 0x3D], rule);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    let Ok(result) = explorer.explore(&mut i2c, &mut executor, &mut DummySerial) else {
+        panic!("explore failed");
+    };
+    assert_eq!(result.found(), &[0x3C, 0x3D]);
+    // [0, 1, 2] fails, [0, 2, 1] is the second order tried.
+    assert_eq!(result.permutations_tested, 2);
+    assert_eq!(
+        i2c.history(0x3C),
+        Some(&[0x00, 0xA0, 0x00, 0xA2, 0x00, 0xA1][..])
+    );
+
+    // A dependency forcing 1 before 2 rules the accepted order out.
+    static CONSTRAINED: [CmdNode; 3] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[1]),
+    ];
+    let explorer = Explorer::<3, 1>::new(&CONSTRAINED);
+    let mut i2c = OracleI2c::<_, 1, 16>::new(&[0x3C], rule);
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    let Ok(result) = explorer.explore(&mut i2c, &mut executor, &mut DummySerial) else {
+        panic!("explore failed");
+    };
+    assert!(result.found().is_empty());
+    assert_eq!(result.permutations_tested, 3);
+}
+
+#[test]
+fn test_guarded_i2c_never_touches_excluded_addrs() {
+    let mut i2c = RecordingI2c::default();
+    {
+        let mut guarded = GuardedI2c::new(&mut i2c, &[0x34]);
+        let Ok(found) = scan_i2c(&mut guarded, &mut DummySerial, 0x00) else {
+            panic!("scan failed");
+        };
+        assert!(!found.contains(&0x34));
+        assert_eq!(found.len(), 0x77 - 0x03);
+
+        assert!(guarded.write(0x34, &[0x00]).is_err());
+        assert!(guarded.write(0x3C, &[0x00]).is_ok());
+    }
+    assert_eq!(i2c.writes, vec![(0x3C, vec![0x00])]);
+}
+
+#[test]
+fn test_deps_converts_usize_lists() {
+    use dvcdbg::deps;
+    use dvcdbg::explore::explorer::CmdNode;
+
+    const OLD_DEPS: &[usize] = &[0, 300];
+    static NODE: CmdNode = CmdNode::new(&[0xAF], deps!(OLD_DEPS));
+    assert_eq!(NODE.deps, &[0u16, 300]);
+    assert_eq!(deps!(&[1, 2, 3]), &[1u16, 2, 3]);
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deps_u8_still_expands() {
+    use dvcdbg::deps_u8;
+    use dvcdbg::explore::explorer::deps_to_u8;
+
+    assert_eq!(deps_u8!(&[1, 2, 3]), &[1u16, 2, 3]);
+    assert_eq!(deps_to_u8::<2>(&[4, 500]), [4, 500]);
+}
+
+#[test]
+fn test_probe_read_uses_a_read() {
+    // The oracle NACKs every write, but reads only NACK on absent addresses.
+    let mut i2c = OracleI2c::<_, 1, 4>::new(&[0x50], |_, _: &[u8], _: &[u8]| false);
+    assert!(i2c.write(0x50, &[0x00]).is_err());
+    assert!(i2c.probe_read(0x50, 1) == Ok(true));
+    assert!(i2c.probe_read(0x51, 64) == Ok(false));
+}
+
+mod fake_driver {
+    pub mod command {
+        pub const DISPLAY_OFF: u8 = 0xAE;
+        pub const SET_MULTIPLEX_RATIO: u8 = 0xA8;
+        pub const DISPLAY_ON: u8 = 0xAF;
+    }
+}
+
+#[test]
+fn test_nodes_from_consts() {
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, Placement};
+    use fake_driver::command::DISPLAY_OFF;
+
+    static NODES: [CmdNode; 3] = nodes_from_consts!([
+        DISPLAY_OFF,
+        fake_driver::command::SET_MULTIPLEX_RATIO @ [0],
+        fake_driver::command::DISPLAY_ON @ [1] => Last,
+    ]);
+    assert_eq!(NODES[1].bytes, &[0xA8]);
+    assert_eq!(NODES[1].deps, &[0]);
+    assert_eq!(NODES[2].placement, Placement::Last);
+    assert!(Explorer::<3, 2>::new(&NODES).is_valid_order(&[0, 1, 2]));
+}
+
+#[test]
+fn test_explore_program_records_and_replays() {
+    use dvcdbg::explore::program::InitProgram;
+    use dvcdbg::explore::runner::explore_program;
+
+    let Ok(program) = explore_program::<_, _, _, _, 3, 3, 2>(
+        &demo_graph::EXPLORER,
+        &mut DummyI2c,
+        &mut DummySerial,
+        demo_graph::PREFIX,
+        DummyDelay,
+    ) else {
+        panic!("explore_program failed");
+    };
+    assert_eq!(program.addr(), 0x03);
+    assert_eq!(
+        program.frames(),
+        &[&[0xAE][..], &[0xD5, 0x80][..], &[0xAF][..]]
+    );
+
+    let mut i2c = RecordingI2c::default();
+    assert!(program.replay(&mut i2c, &mut DummySerial, DummyDelay) == Ok(3));
+    assert_eq!(i2c.writes[1], (0x03, vec![0x00, 0xD5, 0x80]));
+
+    let mut hex = StringWriter::<64>::new();
+    assert!(program.write_hex(&mut hex).is_ok());
+    assert!(hex.as_str().starts_with("03: 00 AE\r\n03: 00 D5 80\r\n"));
+
+    let reordered = InitProgram::<3, 3>::from_order(&demo_graph::EXPLORER, 0x3C, 0x00, &[0, 1, 2]);
+    assert!(reordered.is_ok_and(|p| p.frames().len() == 3));
+}
+
+#[test]
+fn test_init_program_keeps_node_delays() {
+    use dvcdbg::explore::program::InitProgram;
+    use std::cell::RefCell;
+
+    struct Delay<'a>(&'a RefCell<Vec<u32>>);
+    impl DelayCompat for Delay<'_> {
+        fn delay_us(&mut self, us: u32) {
+            self.0.borrow_mut().push(us);
+        }
+    }
+
+    let Ok(program) =
+        InitProgram::<3, 2>::first_sort(&delayed_graph::EXPLORER, 0x3C, delayed_graph::PREFIX)
+    else {
+        panic!("no program");
+    };
+    assert_eq!(program.delays(), &[500, 0, 20]);
+
+    let mut hex = StringWriter::<64>::new();
+    assert!(program.write_hex(&mut hex).is_ok());
+    assert_eq!(
+        hex.as_str(),
+        "3C: 00 01 +500us\r\n3C: 00 AE\r\n3C: 00 AF +20us\r\n"
+    );
+
+    // Each write is followed by the executor's post-write pause, then the node's.
+    let pauses = RefCell::new(Vec::new());
+    let mut i2c = RecordingI2c::default();
+    assert!(program.replay(&mut i2c, &mut DummySerial, Delay(&pauses)) == Ok(3));
+    assert_eq!(*pauses.borrow(), [1000, 500, 1000, 1000, 20]);
+}
+
+#[test]
+fn test_trace_replay_reproduces_explore() {
+    use dvcdbg::compat::mock::ReplayError;
+    use dvcdbg::explore::explorer::{CmdNode, Explorer, PrefixExecutor};
+    use dvcdbg::explore::trace::TracingExecutor;
+
+    static NODES: [CmdNode; 3] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[]),
+        CmdNode::new(&[0xA2], &[]),
+    ];
+    let rule = |_: u8, history: &[u8], frame: &[u8]| {
+        [0xA0, 0xA2, 0xA1].get(history.len() / 2) == frame.get(1)
+    };
+    let explorer = Explorer::<3, 0>::new(&NODES);
+
+    // Capture a run against the "hardware".
+    let mut device = OracleI2c::<_, 1, 16>::new(&[0x3C], rule);
+    let mut tracer = TracingExecutor::<_, 32, 4>::new(PrefixExecutor::<0, 4>::new(0x00, &[]));
+    let Ok(captured) = explorer.explore(&mut device, &mut tracer, &mut DummySerial) else {
+        panic!("capture failed");
+    };
+    assert_eq!(tracer.dropped(), 0);
+
+    // Replay it offline: same result, every record consumed.
+    let mut i2c = TraceReplayI2c::new(tracer.trace());
+    let mut executor = PrefixExecutor::<0, 4>::new(0x00, &[]);
+    let Ok(replayed) = explorer.explore(&mut i2c, &mut executor, &mut DummySerial) else {
+        panic!("replay failed");
+    };
+    assert_eq!(replayed.found(), captured.found());
+    assert_eq!(replayed.permutations_tested, captured.permutations_tested);
+    assert!(i2c.is_exhausted());
+
+    // A write the device never saw is reported as a divergence.
+    let mut i2c = TraceReplayI2c::new(tracer.trace());
+    assert_eq!(
+        i2c.write(0x3C, &[0x00, 0xA1]),
+        Err(ReplayError::Diverged { index: 0 })
+    );
+    assert!(i2c.probe(0x3C) == Ok(true));
+    assert!(i2c.probe(0x3D) == Ok(false));
+}
+
+#[test]
+fn test_scan_speed_sweep_counts_acks_per_speed() {
+    // A marginal device: always ACKs at 100 kHz, every other probe at 400 kHz.
+    struct MarginalI2c {
+        hz: u32,
+        probes: u32,
+    }
+
+    impl I2cCompat for MarginalI2c {
+        type Error = core::convert::Infallible;
+
+        fn write(&mut self, _addr: u8, _bytes: &[u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn read(&mut self, _addr: u8, _buffer: &mut [u8]) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn write_read(
+            &mut self,
+            _addr: u8,
+            _bytes: &[u8],
+            _buffer: &mut [u8],
+        ) -> Result<(), Self::Error> {
+            Ok(())
+        }
+        fn probe(&mut self, addr: u8) -> Result<bool, Self::Error> {
+            self.probes += 1;
+            Ok(addr == 0x3C && (self.hz <= 100_000 || self.probes.is_multiple_of(2)))
+        }
+        fn is_nack(&self, _error: &Self::Error) -> bool {
+            false
+        }
+    }
+
+    let mut i2c = MarginalI2c { hz: 0, probes: 0 };
+    let set_speed = |i2c: &mut MarginalI2c, hz: u32| i2c.hz = hz;
+    let Ok(sweep) = scan_speed_sweep::<_, _, _, 2>(
+        &mut i2c,
+        &mut DummySerial,
+        0x3C,
+        &[100_000, 400_000],
+        10,
+        set_speed,
+    ) else {
+        panic!("sweep failed");
+    };
+    assert_eq!(sweep.as_slice(), &[(100_000, 10), (400_000, 5)]);
+    assert_eq!(i2c.probes, 20);
+
+    let too_many = scan_speed_sweep::<_, _, _, 1>(
+        &mut i2c,
+        &mut DummySerial,
+        0x3C,
+        &[100_000, 400_000],
+        10,
+        set_speed,
+    );
+    assert!(too_many.is_err());
+}
+
+#[test]
+fn test_topological_iter_reports_cycle_nodes() {
+    use dvcdbg::compat::util::BitFlags;
+    use dvcdbg::explore::explorer::{CmdNode, Explorer};
+    use dvcdbg::explore::runner::one_topological_explorer;
+
+    // 1 and 2 depend on each other; 3 is stuck behind 2; 0 is free.
+    static NODES: [CmdNode; 4] = [
+        CmdNode::new(&[0xA0], &[]),
+        CmdNode::new(&[0xA1], &[2]),
+        CmdNode::new(&[0xA2], &[1]),
+        CmdNode::new(&[0xA3], &[2]),
+    ];
+    let explorer = Explorer::<4, 3>::new(&NODES);
+
+    let failed = BitFlags::new();
+    let mut iter = explorer.topological_iter(&failed).ok().unwrap();
+    assert_eq!(iter.by_ref().collect::<Vec<_>>(), vec![0]);
+    assert!(iter.is_cycle_detected());
+    assert_eq!(
+        iter.cycle_nodes().iter_set().collect::<Vec<_>>(),
+        vec![1, 2, 3]
+    );
+
+    let mut writer = StringWriter::<1024>::new();
+    let res = one_topological_explorer::<_, _, _, 4, 0, 2, 3>(
+        &explorer,
+        &mut DummyI2c,
+        &mut writer,
+        0x00,
+    );
+    assert!(res.err() == Some(ExplorerError::DependencyCycle));
+    #[cfg(not(feature = "no-log"))]
+    assert!(
+        writer
+            .as_str()
+            .contains("cycle involves nodes: 01 02 03\r\n")
+    );
+}
+
+#[test]
+fn test_pruning_plan_logs_batches_without_writing() {
+    use core::cell::Cell;
+    use dvcdbg::explore::runner::pruning_plan;
+
+    let writes = Cell::new(0);
+    let mut i2c = OracleI2c::<_, 2, 16>::new(&[0x3C, 0x3D], |_, _: &[u8], _: &[u8]| {
+        writes.set(writes.get() + 1);
+        true
+    });
+    let mut writer = StringWriter::<1024>::new();
+    let plan = pruning_plan::<
+        _,
+        _,
+        _,
+        { demo_graph::N },
+        { demo_graph::BATCH_BUFFER_SIZE },
+        { demo_graph::MAX_DEPS },
+    >(
+        &demo_graph::EXPLORER,
+        &mut i2c,
+        &mut writer,
+        demo_graph::PREFIX,
+    );
+    let Ok(batch) = plan else {
+        panic!("plan failed");
+    };
+    assert_eq!(batch.as_slice(), &[0x00, 0xAE, 0xD5, 0x80, 0xAF]);
+    assert_eq!(writes.get(), 0);
+    #[cfg(not(feature = "no-log"))]
+    assert!(
+        writer
+            .as_str()
+            .contains("[plan] 3C: 00 AE D5 80 AF\r\n[plan] 3D: 00 AE D5 80 AF\r\n")
+    );
+}